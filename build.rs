@@ -1,9 +1,13 @@
 fn main() {
     println!("cargo::rustc-check-cfg=cfg(avx512_stable)");
+    println!("cargo::rustc-check-cfg=cfg(scoped_threads)");
     // `if rustversion::cfg!(...)` is not supported in older Rust versions
     if avx512_stable() {
         println!("cargo:rustc-cfg=avx512_stable");
     }
+    if scoped_threads() {
+        println!("cargo:rustc-cfg=scoped_threads");
+    }
 }
 
 #[rustversion::since(1.89)]
@@ -15,3 +19,13 @@ fn avx512_stable() -> bool {
 fn avx512_stable() -> bool {
     false
 }
+
+#[rustversion::since(1.63)]
+fn scoped_threads() -> bool {
+    true
+}
+
+#[rustversion::before(1.63)]
+fn scoped_threads() -> bool {
+    false
+}
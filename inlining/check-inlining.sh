@@ -25,7 +25,9 @@ inline_ignore_pattern=\
 'std_detect::detect::|'\
 '::fmt::|'\
 '^\$x\.|'\
+'^anon\.|'\
 '^<T as core::convert::From<T>>::from$|'\
+'^<T as core::convert::TryFrom<U>>::try_from$|'\
 '^core::str::Utf8Error::error_len$|'\
 '^core::str::Utf8Error::valid_up_to$|'\
 '^core::str::from_utf8$|'\
@@ -0,0 +1,61 @@
+//! The `ascii` module provides SIMD-accelerated checks for ASCII input.
+//!
+//! Use [`classify()`] to decide between ASCII-only fast paths, such as byte indexing or
+//! ASCII case mapping, and the general UTF-8 code path with a single scan of the input:
+//!
+//! ```rust
+//! use simdutf8::ascii::{classify, Classification};
+//!
+//! assert_eq!(classify(b"plain ASCII"), Classification::Ascii);
+//! assert_eq!(classify("Grüße".as_bytes()), Classification::Utf8);
+//! assert_eq!(classify(b"\xFF"), Classification::Invalid);
+//! ```
+
+use crate::implementation::validate_utf8_basic;
+
+/// Result of [`classify()`].
+#[derive(Copy, Eq, PartialEq, Clone, Debug)]
+pub enum Classification {
+    /// The input consists of ASCII bytes only and is thus also valid UTF-8.
+    Ascii,
+    /// The input is valid UTF-8 and contains at least one non-ASCII char.
+    Utf8,
+    /// The input is not valid UTF-8.
+    Invalid,
+}
+
+/// Checks if all bytes of the input are ASCII.
+///
+/// Analogue to [`slice::is_ascii()`](https://doc.rust-lang.org/std/primitive.slice.html#method.is_ascii).
+#[inline]
+#[must_use]
+pub fn is_ascii(input: &[u8]) -> bool {
+    first_non_ascii(input).is_none()
+}
+
+/// Returns the position of the first byte of the input which is not ASCII or `None` if all
+/// bytes are ASCII.
+///
+/// All bytes before the returned position are ASCII, so the prefix is valid UTF-8.
+#[inline]
+#[must_use]
+pub fn first_non_ascii(input: &[u8]) -> Option<usize> {
+    crate::implementation::first_non_ascii(input)
+}
+
+/// Classifies the input as ASCII, valid UTF-8 with non-ASCII chars or invalid UTF-8.
+///
+/// The ASCII prefix of the input is only scanned once, UTF-8 validation starts at the
+/// first non-ASCII byte. This is faster than calling [`is_ascii()`] and
+/// [`basic::from_utf8()`](crate::basic::from_utf8()) one after the other.
+#[inline]
+#[must_use]
+pub fn classify(input: &[u8]) -> Classification {
+    first_non_ascii(input).map_or(Classification::Ascii, |pos| {
+        // SAFETY: first_non_ascii() only returns positions in bounds
+        match unsafe { validate_utf8_basic(input.get_unchecked(pos..)) } {
+            Ok(()) => Classification::Utf8,
+            Err(_) => Classification::Invalid,
+        }
+    })
+}
@@ -5,6 +5,8 @@
 //!
 //! If you need detailed error information use the functions from the [`crate::compat`] module instead.
 
+use core::mem::MaybeUninit;
+use core::ops::Deref;
 use core::str::{from_utf8_unchecked, from_utf8_unchecked_mut};
 
 use crate::implementation::validate_utf8_basic;
@@ -55,6 +57,38 @@ pub fn from_utf8_mut(input: &mut [u8]) -> Result<&mut str, Utf8Error> {
     }
 }
 
+/// Validates the concatenation of the passed slices as one UTF-8 string without copying them.
+///
+/// Chars may span slice boundaries. Accepts slices of anything dereferencing to `[u8]`, e.g.
+/// `&[u8]`, [`std::io::IoSlice`] or [`std::io::IoSliceMut`].
+///
+/// # Errors
+/// Will return the zero-sized Err([`Utf8Error`]) on if the concatenated input contains invalid
+/// UTF-8.
+#[inline]
+pub fn validate_vectored<S: Deref<Target = [u8]>>(slices: &[S]) -> Result<(), Utf8Error> {
+    crate::compat::validate_vectored(slices).map_err(|_| Utf8Error)
+}
+
+/// Copies `src` to the start of `dst` and validates it in a single pass.
+///
+/// Every byte of `src` is read only once and the validated bytes are the ones which are written
+/// to `dst`, so the returned `str` is a private copy which is guaranteed to be valid UTF-8.
+///
+/// # Errors
+/// Will return the zero-sized Err([`Utf8Error`]) on if the input contains invalid UTF-8. The
+/// contents of `dst` are unspecified in that case.
+///
+/// # Panics
+/// If `dst` is shorter than `src`.
+#[inline]
+pub fn validate_and_copy<'a>(
+    src: &[u8],
+    dst: &'a mut [MaybeUninit<u8>],
+) -> Result<&'a str, Utf8Error> {
+    crate::compat::validate_and_copy(src, dst).map_err(|_| Utf8Error)
+}
+
 /// Allows direct access to the platform-specific unsafe validation implementations.
 #[cfg(feature = "public_imp")]
 pub mod imp {
@@ -138,6 +172,141 @@ pub mod imp {
         unsafe fn finalize(self) -> core::result::Result<(), basic::Utf8Error>;
     }
 
+    /// Extension of [`Utf8Validator`] taking and restoring backend-independent snapshots of the
+    /// validation state.
+    pub trait Utf8ValidatorSnapshot: Utf8Validator {
+        /// Takes a backend-independent snapshot of the validation state.
+        ///
+        /// # Safety
+        /// This implementation requires CPU SIMD features specified by the module it resides in.
+        /// It is undefined behavior to call it if the required CPU features are not available.
+        unsafe fn snapshot(&self) -> Utf8ValidatorState;
+
+        /// Creates a validator continuing from a snapshot, which can have been taken with any
+        /// implementation.
+        ///
+        /// # Safety
+        /// This implementation requires CPU SIMD features specified by the module it resides in.
+        /// It is undefined behavior to call it if the required CPU features are not available.
+        #[must_use]
+        unsafe fn restore(state: &Utf8ValidatorState) -> Self
+        where
+            Self: Sized;
+    }
+
+    /// Backend-independent state of a [`Utf8Validator`].
+    ///
+    /// Taken with [`Utf8ValidatorSnapshot::snapshot()`] and restored with
+    /// [`Utf8ValidatorSnapshot::restore()`] into the same or any other implementation, e.g. to
+    /// checkpoint the progress of a long running validation. [`fallback::Utf8ValidatorImp`]
+    /// continues it on CPUs without SIMD extensions. It can be converted to and from a
+    /// portable byte representation of [`Self::SERIALIZED_LEN`] bytes.
+    ///
+    /// ```rust
+    /// use simdutf8::basic::imp::{Utf8Validator, Utf8ValidatorSnapshot, Utf8ValidatorState};
+    ///
+    /// # #[cfg(target_arch = "x86_64")]
+    /// # fn main() {
+    /// use simdutf8::basic::imp::x86::{avx2, sse42};
+    ///
+    /// if !std::is_x86_feature_detected!("avx2") {
+    ///     return;
+    /// }
+    /// unsafe {
+    ///     let mut validator = avx2::Utf8ValidatorImp::new();
+    ///     validator.update(b"Gr\xC3\xBC\xC3");
+    ///     let bytes = validator.snapshot().to_bytes();
+    ///
+    ///     let state = Utf8ValidatorState::from_bytes(&bytes).unwrap();
+    ///     let mut validator = sse42::Utf8ValidatorImp::restore(&state);
+    ///     validator.update(b"\x9Fe");
+    ///     assert!(validator.finalize().is_ok());
+    /// }
+    /// # }
+    /// # #[cfg(not(target_arch = "x86_64"))]
+    /// # fn main() { }
+    /// ```
+    #[derive(Copy, Clone)]
+    pub struct Utf8ValidatorState {
+        pub(crate) error: bool,
+        pub(crate) prev: [u8; 3],
+        pub(crate) pending: [u8; 64],
+        pub(crate) pending_len: usize,
+    }
+
+    impl Utf8ValidatorState {
+        /// Length of the byte representation.
+        pub const SERIALIZED_LEN: usize = 70;
+
+        /// Version of the byte representation, stored in its first byte.
+        const FORMAT_VERSION: u8 = 1;
+
+        /// Returns the byte representation.
+        ///
+        /// The layout is: format version, error flag, the last three bytes of the last block
+        /// containing non-ASCII bytes, number of buffered bytes and the buffered bytes, padded
+        /// with zeros to 64 bytes.
+        #[flexpect::e(clippy::cast_possible_truncation)] // at most 63 bytes are pending
+        #[must_use]
+        #[inline]
+        pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_LEN] {
+            let mut bytes = [0; Self::SERIALIZED_LEN];
+            bytes[0] = Self::FORMAT_VERSION;
+            bytes[1] = u8::from(self.error);
+            bytes[2..5].copy_from_slice(&self.prev);
+            bytes[5] = self.pending_len as u8;
+            bytes[6..].copy_from_slice(&self.pending);
+            bytes
+        }
+
+        /// Reads a state from its byte representation.
+        ///
+        /// Returns `None` if `bytes` is not a representation returned by [`Self::to_bytes()`].
+        #[must_use]
+        #[inline]
+        pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+            if bytes.len() != Self::SERIALIZED_LEN
+                || bytes[0] != Self::FORMAT_VERSION
+                || bytes[1] > 1
+                || bytes[5] >= 64
+                || bytes[6 + bytes[5] as usize..].iter().any(|&b| b != 0)
+            {
+                return None;
+            }
+            let mut state = Self {
+                error: bytes[1] == 1,
+                prev: [bytes[2], bytes[3], bytes[4]],
+                pending: [0; 64],
+                pending_len: bytes[5] as usize,
+            };
+            state.pending.copy_from_slice(&bytes[6..]);
+            Some(state)
+        }
+    }
+
+    // arrays longer than 32 bytes do not implement `PartialEq` on Rust 1.38
+    impl PartialEq for Utf8ValidatorState {
+        #[inline]
+        fn eq(&self, other: &Self) -> bool {
+            self.error == other.error
+                && self.prev == other.prev
+                && self.pending[..] == other.pending[..]
+                && self.pending_len == other.pending_len
+        }
+    }
+
+    impl Eq for Utf8ValidatorState {}
+
+    #[flexpect::e(clippy::missing_fields_in_debug)] // the buffered bytes are an internal detail
+    impl core::fmt::Debug for Utf8ValidatorState {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("Utf8ValidatorState")
+                .field("error", &self.error)
+                .field("pending_len", &self.pending_len)
+                .finish()
+        }
+    }
+
     /// Like [`Utf8Validator`] this low-level API is for streaming validation of UTF-8 data.
     ///
     /// It has additional restrictions imposed on how the input is passed in to allow
@@ -194,6 +363,14 @@ pub mod imp {
         ) -> core::result::Result<(), basic::Utf8Error>;
     }
 
+    /// Includes the implementation which works on all CPUs.
+    ///
+    /// It does not provide a [`ChunkedUtf8Validator`], use [`crate::basic::from_utf8()`] to
+    /// validate complete inputs.
+    pub mod fallback {
+        pub use crate::implementation::fallback::Utf8ValidatorImp;
+    }
+
     /// Includes the x86/x86-64 SIMD implementations.
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     pub mod x86 {
@@ -0,0 +1,321 @@
+//! The `batch` module validates many short strings at once.
+//!
+//! Validating each string with [`basic::from_utf8()`](crate::basic::from_utf8()) selects the
+//! implementation and sets up the SIMD state for every call. The functions in this module do
+//! that once per batch and pack several short strings into one SIMD block:
+//!
+//! ```rust
+//! use simdutf8::batch::{validate_many_compat, validate_many_into};
+//!
+//! let strings: [&[u8]; 3] = [b"first", b"sec\xFFond", "dritte".as_bytes()];
+//! let mut results = [false; 3];
+//! validate_many_into(&strings, &mut results);
+//! assert_eq!(results, [true, false, true]);
+//!
+//! let err = validate_many_compat(&strings).unwrap_err();
+//! assert_eq!(err.index(), 1);
+//! assert_eq!(err.error().valid_up_to(), 3);
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt::{Debug, Display, Formatter};
+use core::iter::FusedIterator;
+use core::slice::ChunksExact;
+use core::str::from_utf8_unchecked;
+
+use crate::compat::{self, Utf8Error};
+use crate::implementation::helpers::is_continuation_byte;
+
+/// Number of inputs validated at once by [`validate_many_compat()`] before checking for errors.
+const COMPAT_GROUP_SIZE: usize = 64;
+
+/// UTF-8 error of one element of a batch.
+///
+/// Contains the index of the first invalid element and its [`compat::Utf8Error`].
+#[derive(Copy, Eq, PartialEq, Clone, Debug)]
+pub struct BatchError {
+    pub(crate) index: usize,
+    pub(crate) error: Utf8Error,
+}
+
+impl BatchError {
+    /// The index of the first invalid element.
+    #[inline]
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The validation error of the invalid element, relative to the start of the element.
+    #[inline]
+    #[must_use]
+    pub fn error(&self) -> Utf8Error {
+        self.error
+    }
+}
+
+impl Display for BatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "element {}: {}", self.index, self.error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BatchError {}
+
+/// Validates each of the inputs and stores whether it is valid UTF-8 in the corresponding
+/// element of `results`.
+///
+/// # Panics
+/// If `inputs` and `results` have different lengths.
+#[inline]
+pub fn validate_many_into(inputs: &[&[u8]], results: &mut [bool]) {
+    crate::implementation::validate_many(inputs, results);
+}
+
+/// Validates each of the inputs and returns whether it is valid UTF-8.
+#[cfg(feature = "std")]
+#[inline]
+#[must_use]
+pub fn validate_many(inputs: &[&[u8]]) -> Vec<bool> {
+    let mut results = vec![false; inputs.len()];
+    crate::implementation::validate_many(inputs, &mut results);
+    results
+}
+
+/// Validates all inputs and returns the index and error of the first invalid one.
+///
+/// # Errors
+/// Will return Err([`BatchError`]) if one of the inputs is not valid UTF-8.
+#[inline]
+pub fn validate_many_compat(inputs: &[&[u8]]) -> Result<(), BatchError> {
+    let mut results = [false; COMPAT_GROUP_SIZE];
+    for (group_idx, group) in inputs.chunks(COMPAT_GROUP_SIZE).enumerate() {
+        let results = &mut results[..group.len()];
+        crate::implementation::validate_many(group, results);
+        if let Some(pos) = results.iter().position(|valid| !valid) {
+            let index = group_idx * COMPAT_GROUP_SIZE + pos;
+            if let Err(error) = compat::from_utf8(inputs[index]) {
+                return Err(BatchError { index, error });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validates strings stored as a values buffer and offsets, as used by Apache Arrow, with
+/// 32-bit offsets.
+///
+/// String `i` consists of the bytes `values[offsets[i]..offsets[i + 1]]`. The values between
+/// the first and the last offset are validated in one pass and every offset is checked to be on
+/// a char boundary.
+///
+/// ```rust
+/// use simdutf8::batch::validate_offsets_i32;
+///
+/// let values = "aböcd".as_bytes();
+/// assert!(validate_offsets_i32(&[0, 2, 4, 6], values).is_ok());
+/// // the second string ends in the middle of the ö
+/// assert_eq!(validate_offsets_i32(&[0, 2, 3, 6], values).unwrap_err().index(), 1);
+/// ```
+///
+/// # Errors
+/// Will return Err([`BatchError`]) with the index of the first string which is not valid
+/// UTF-8 by itself.
+///
+/// # Panics
+/// If the offsets are negative, decreasing or larger than the length of `values`.
+#[inline]
+pub fn validate_offsets_i32(offsets: &[i32], values: &[u8]) -> Result<(), BatchError> {
+    validate_offsets(offsets, values)
+}
+
+/// Validates strings stored as a values buffer and offsets, as used by Apache Arrow, with
+/// 64-bit offsets.
+///
+/// See [`validate_offsets_i32()`].
+///
+/// # Errors
+/// Will return Err([`BatchError`]) with the index of the first string which is not valid
+/// UTF-8 by itself.
+///
+/// # Panics
+/// If the offsets are negative, decreasing or larger than the length of `values`.
+#[inline]
+pub fn validate_offsets_i64(offsets: &[i64], values: &[u8]) -> Result<(), BatchError> {
+    validate_offsets(offsets, values)
+}
+
+#[inline]
+fn validate_offsets<O>(offsets: &[O], values: &[u8]) -> Result<(), BatchError>
+where
+    O: Copy,
+    usize: TryFrom<O>,
+{
+    let offset = |idx: usize| {
+        usize::try_from(offsets[idx])
+            .ok()
+            .filter(|offset| *offset <= values.len())
+            .expect("offset out of bounds")
+    };
+    if offsets.len() < 2 {
+        return Ok(());
+    }
+    let first = offset(0);
+    let last = offset(offsets.len() - 1);
+
+    // Find the first string which is invalid assuming the offsets are correct. The strings before
+    // it are valid if all offsets before it are char boundaries.
+    let mut candidate = match compat::from_utf8(&values[first..last]) {
+        Ok(_) => offsets.len() - 1,
+        Err(err) => {
+            let error_pos = first + err.valid_up_to();
+            let (mut lo, mut hi) = (0, offsets.len() - 1);
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if offset(mid + 1) > error_pos {
+                    hi = mid;
+                } else {
+                    lo = mid + 1;
+                }
+            }
+            lo
+        }
+    };
+
+    // Check the offsets before it. An offset which is not a char boundary splits a char, so the
+    // last non-empty string ending at it is invalid.
+    let mut prev = first;
+    for idx in 1..offsets.len() {
+        let current = offset(idx);
+        assert!(prev <= current, "offsets must not decrease");
+        if idx <= candidate && current < last && is_continuation_byte(values[current]) {
+            candidate = (0..idx).rev().find(|i| offset(*i) < current).unwrap_or(0);
+        }
+        prev = current;
+    }
+
+    for index in candidate..offsets.len() - 1 {
+        if let Err(error) = compat::from_utf8(&values[offset(index)..offset(index + 1)]) {
+            return Err(BatchError { index, error });
+        }
+    }
+    Ok(())
+}
+
+/// Padding of fixed-width fields, which is trimmed from the end of each field.
+#[derive(Copy, Eq, PartialEq, Clone, Debug)]
+pub enum Padding {
+    /// Fields are padded with NUL bytes, e.g. C strings in fixed-size buffers.
+    Nul,
+    /// Fields are padded with spaces, e.g. SQL `CHAR(n)` columns.
+    Space,
+}
+
+impl Padding {
+    #[inline]
+    fn byte(self) -> u8 {
+        match self {
+            Self::Nul => b'\0',
+            Self::Space => b' ',
+        }
+    }
+}
+
+/// Validates a buffer of fixed-width fields and returns an iterator over the fields with the
+/// padding trimmed.
+///
+/// The whole buffer is validated in one pass and every field is checked to start on a char
+/// boundary.
+///
+/// ```rust
+/// use simdutf8::batch::{validate_fixed_width, Padding};
+///
+/// let fields: Vec<&str> = validate_fixed_width(b"one  two  three", 5, Padding::Space)
+///     .unwrap()
+///     .collect();
+/// assert_eq!(fields, ["one", "two", "three"]);
+///
+/// let err = validate_fixed_width(b"ok\0\0fa\xFFl", 4, Padding::Nul).unwrap_err();
+/// assert_eq!(err.index(), 1);
+/// ```
+///
+/// # Errors
+/// Will return Err([`BatchError`]) with the index of the first field which is not valid UTF-8
+/// by itself.
+///
+/// # Panics
+/// If `stride` is zero or the length of the buffer is not a multiple of it.
+#[inline]
+pub fn validate_fixed_width(
+    buffer: &[u8],
+    stride: usize,
+    padding: Padding,
+) -> Result<FixedWidthFields<'_>, BatchError> {
+    assert!(stride != 0, "stride must not be zero");
+    assert!(
+        buffer.chunks_exact(stride).remainder().is_empty(),
+        "buffer length must be a multiple of the stride"
+    );
+    let fields = buffer.len() / stride;
+    let mut candidate = match compat::from_utf8(buffer) {
+        Ok(_) => fields,
+        Err(err) => err.valid_up_to() / stride,
+    };
+    // A field which does not start on a char boundary splits a char with the previous field.
+    if let Some(idx) = (1..=core::cmp::min(candidate, fields.saturating_sub(1)))
+        .find(|idx| is_continuation_byte(buffer[idx * stride]))
+    {
+        candidate = idx - 1;
+    }
+    for (index, field) in buffer.chunks_exact(stride).enumerate().skip(candidate) {
+        if let Err(error) = compat::from_utf8(field) {
+            return Err(BatchError { index, error });
+        }
+    }
+    Ok(FixedWidthFields {
+        fields: buffer.chunks_exact(stride),
+        padding,
+    })
+}
+
+/// Iterator over the fields of a buffer returned by [`validate_fixed_width()`].
+#[derive(Clone)]
+pub struct FixedWidthFields<'a> {
+    fields: ChunksExact<'a, u8>,
+    padding: Padding,
+}
+
+impl<'a> Iterator for FixedWidthFields<'a> {
+    type Item = &'a str;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a str> {
+        let field = self.fields.next()?;
+        let padding = self.padding.byte();
+        let len = field
+            .iter()
+            .rposition(|b| *b != padding)
+            .map_or(0, |pos| pos + 1);
+        // SAFETY: the field is valid UTF-8 and the padding is ASCII
+        unsafe { Some(from_utf8_unchecked(field.get_unchecked(..len))) }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.fields.size_hint()
+    }
+}
+
+impl Debug for FixedWidthFields<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        // the buffer is omitted since it might be large
+        f.debug_struct("FixedWidthFields")
+            .field("padding", &self.padding)
+            .finish()
+    }
+}
+
+impl ExactSizeIterator for FixedWidthFields<'_> {}
+
+impl FusedIterator for FixedWidthFields<'_> {}
@@ -0,0 +1,227 @@
+//! The `bitmap` module validates UTF-8 and returns bit masks of each 64-byte block.
+//!
+//! SIMD parsers and tokenizers work on 64-byte blocks and combine `u64` masks with bit tricks.
+//! [`BlockMasks`] holds a mask of the char starts and a mask of the bytes at which errors were
+//! detected for each block. The masks are the same for all implementations:
+//!
+//! ```rust
+//! use simdutf8::bitmap::{block_masks, BlockMasks};
+//!
+//! let mut masks = [BlockMasks::default(); 2];
+//! let input = "aö€😊".repeat(10);
+//! assert!(block_masks(input.as_bytes(), &mut masks).is_ok());
+//! assert_eq!(masks[0].char_starts & 0b11_1111_1111, 0b00_0100_1011);
+//!
+//! let mut masks = [BlockMasks::default(); 1];
+//! assert!(block_masks(b"ab\xE2\x82cd\x80", &mut masks).is_err());
+//! // the bytes which cannot continue the sequence before them
+//! assert_eq!(masks[0].errors, 0b101_0000);
+//! ```
+//!
+//! [`validate_with_byte_bitmaps()`] finds up to 16 ASCII bytes, e.g. the structural characters
+//! of a format, in the same pass which validates the input:
+//!
+//! ```rust
+//! use simdutf8::bitmap::{validate_with_byte_bitmaps, ByteSet};
+//!
+//! let set = ByteSet::new(b"\",\n").unwrap();
+//! let mut bitmaps = [0; 3];
+//! validate_with_byte_bitmaps(b"\"\xC3\xA4\",1\n", &set, &mut bitmaps).unwrap();
+//! assert_eq!(bitmaps, [0b1001, 0b1_0000, 0b100_0000]);
+//! ```
+
+use core::fmt::{Debug, Formatter};
+
+use crate::basic::Utf8Error;
+use crate::compat;
+use crate::implementation::helpers::block_count;
+use crate::implementation::stream::incomplete_char_start;
+
+/// Bit masks of a 64-byte block, bit `i` refers to byte `i` of the block.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BlockMasks {
+    /// Bytes which are not UTF-8 continuation bytes, i.e. the starts of the chars of valid
+    /// UTF-8.
+    pub char_starts: u64,
+    /// Bytes at which an error was detected.
+    ///
+    /// An error is detected at the first byte which cannot follow the bytes before it: a byte
+    /// which is not a continuation byte after an incomplete sequence, an unexpected
+    /// continuation byte, the second byte of an overlong encoding, a surrogate or a code point
+    /// above U+10FFFF and the byte after an invalid lead byte.
+    pub errors: u64,
+}
+
+impl BlockMasks {
+    /// Returns `true` if no error was detected in the block.
+    #[inline]
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.errors == 0
+    }
+}
+
+/// Streaming validator computing the [`BlockMasks`] of its input.
+///
+/// Chars may be split across updates, errors are detected across the boundaries.
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub struct BlockValidator {
+    /// The last three bytes of the input so far.
+    prev: [u8; 3],
+    error: bool,
+}
+
+impl BlockValidator {
+    /// Creates a new validator.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates the next part of the input and stores the masks of its blocks in `masks`.
+    ///
+    /// The blocks start at the start of `input`, the last block is partial if the length of
+    /// the input is not a multiple of 64.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if an error was detected in any of the blocks. An
+    /// incomplete char at the end of the input is reported by the next update or
+    /// [`finish()`](Self::finish).
+    ///
+    /// # Panics
+    /// Panics if `masks` has less elements than `input` has blocks.
+    #[inline]
+    pub fn update(&mut self, input: &[u8], masks: &mut [BlockMasks]) -> Result<(), Utf8Error> {
+        crate::implementation::block_masks(input, self.prev, masks);
+        for &b in &input[input.len().saturating_sub(3)..] {
+            self.prev = [self.prev[1], self.prev[2], b];
+        }
+        if masks[..block_count(input.len())]
+            .iter()
+            .any(|masks| !masks.is_valid())
+        {
+            self.error = true;
+            return Err(Utf8Error {});
+        }
+        Ok(())
+    }
+
+    /// Finishes validation.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if an error was detected in any update or the input ends
+    /// with an incomplete char.
+    #[inline]
+    pub fn finish(&self) -> Result<(), Utf8Error> {
+        if self.error || incomplete_char_start(&self.prev) != self.prev.len() {
+            Err(Utf8Error {})
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[flexpect::e(clippy::missing_fields_in_debug)] // the validation state is an internal detail
+impl Debug for BlockValidator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BlockValidator")
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+/// Validates the input and stores the masks of its blocks in `masks`.
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the input is not valid UTF-8. An incomplete char at the end
+/// of the input is not reported in the masks.
+///
+/// # Panics
+/// Panics if `masks` has less elements than `input` has blocks.
+#[inline]
+pub fn block_masks(input: &[u8], masks: &mut [BlockMasks]) -> Result<(), Utf8Error> {
+    let mut validator = BlockValidator::new();
+    validator.update(input, masks)?;
+    validator.finish()
+}
+
+/// Maximum number of bytes of a [`ByteSet`].
+const MAX_SET_LEN: usize = 16;
+
+/// Set of up to 16 ASCII bytes whose occurrences are found by
+/// [`validate_with_byte_bitmaps()`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ByteSet {
+    bytes: [u8; MAX_SET_LEN],
+    len: usize,
+}
+
+impl ByteSet {
+    /// Maximum number of bytes in a set.
+    pub const MAX_LEN: usize = MAX_SET_LEN;
+
+    /// Creates a set of the given bytes in the given order.
+    ///
+    /// Returns `None` if there are more than [`MAX_LEN`](Self::MAX_LEN) bytes or a byte is not
+    /// ASCII. Non-ASCII bytes are only parts of multi-byte chars in valid UTF-8.
+    #[inline]
+    #[must_use]
+    pub fn new(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() > Self::MAX_LEN || !bytes.is_ascii() {
+            return None;
+        }
+        let mut set = Self {
+            bytes: [0; Self::MAX_LEN],
+            len: bytes.len(),
+        };
+        set.bytes[..bytes.len()].copy_from_slice(bytes);
+        Some(set)
+    }
+
+    /// Returns the bytes of the set.
+    #[inline]
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    /// Returns the number of bytes in the set.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the set is empty.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Validates the input and stores the masks of the occurrences of the bytes of `set` in each
+/// 64-byte block in the same pass.
+///
+/// The masks of block `i` are stored at `bitmaps[i * set.len()..(i + 1) * set.len()]`, in the
+/// order of the bytes of the set. The bitmaps are unspecified if the input is not valid UTF-8.
+///
+/// # Errors
+/// Will return Err([`compat::Utf8Error`]) if the input is not valid UTF-8.
+///
+/// # Panics
+/// Panics if `bitmaps` has less than `set.len()` elements for each block of the input.
+#[inline]
+pub fn validate_with_byte_bitmaps<'a>(
+    input: &'a [u8],
+    set: &ByteSet,
+    bitmaps: &mut [u64],
+) -> Result<&'a str, compat::Utf8Error> {
+    if set.is_empty() {
+        return compat::from_utf8(input);
+    }
+    crate::implementation::validate_with_byte_bitmaps(input, set.as_bytes(), bitmaps)?;
+    // SAFETY: the input was just validated
+    unsafe { Ok(core::str::from_utf8_unchecked(input)) }
+}
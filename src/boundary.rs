@@ -0,0 +1,144 @@
+//! The `boundary` module contains helpers for working with char boundaries in UTF-8 data.
+//!
+//! A char boundary is a position in a byte sequence where a UTF-8 char starts or the end of the
+//! sequence. [`floor_char_boundary()`] and [`ceil_char_boundary()`] find the nearest boundary
+//! before or after an index. The other functions build on them and validate their result with
+//! the SIMD implementation:
+//!
+//! ```rust
+//! use simdutf8::boundary::{pieces, resync, truncate};
+//!
+//! // truncate to a byte budget without splitting a char
+//! assert_eq!(truncate("Grüße".as_bytes(), 4).unwrap(), "Grü");
+//!
+//! // split into pieces of at most 4 bytes each
+//! let split: Vec<&str> = pieces("Grüße".as_bytes(), 4).unwrap().collect();
+//! assert_eq!(split, ["Grü", "ße"]);
+//!
+//! // start reading in the middle of the ü
+//! assert_eq!(resync(&"Grüße".as_bytes()[3..]).unwrap(), "ße");
+//! ```
+//!
+//! Errors are reported with offsets relative to the passed input.
+
+use core::iter::FusedIterator;
+use core::str::from_utf8_unchecked;
+
+use crate::compat::{from_utf8, Utf8Error};
+use crate::implementation::helpers::is_continuation_byte;
+
+/// Maximum number of continuation bytes in a UTF-8 sequence.
+const MAX_CONTINUATION_BYTES: usize = 3;
+
+/// Returns the nearest char boundary at or before `index`.
+///
+/// Indices past the end of the input are clamped to the input length. At most three bytes are
+/// skipped, which is enough for valid UTF-8. If no boundary is found within that range the input
+/// is not valid UTF-8 at that position and `index` itself is returned.
+#[inline]
+#[must_use]
+pub fn floor_char_boundary(input: &[u8], index: usize) -> usize {
+    if index >= input.len() {
+        return input.len();
+    }
+    (0..=core::cmp::min(MAX_CONTINUATION_BYTES, index))
+        .find(|i| !is_continuation_byte(input[index - i]))
+        .map_or(index, |i| index - i)
+}
+
+/// Returns the nearest char boundary at or after `index`.
+///
+/// Indices past the end of the input are clamped to the input length. At most three bytes are
+/// skipped, which is enough for valid UTF-8. If no boundary is found within that range the input
+/// is not valid UTF-8 at that position and `index` itself is returned.
+#[inline]
+#[must_use]
+pub fn ceil_char_boundary(input: &[u8], index: usize) -> usize {
+    if index >= input.len() {
+        return input.len();
+    }
+    (0..=core::cmp::min(MAX_CONTINUATION_BYTES, input.len() - index))
+        .find(|i| index + i == input.len() || !is_continuation_byte(input[index + i]))
+        .map_or(index, |i| index + i)
+}
+
+/// Truncates the input to at most `max_len` bytes without splitting a char and validates the
+/// result.
+///
+/// The bytes after the truncation point are not validated.
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the truncated input is not valid UTF-8.
+#[inline]
+pub fn truncate(input: &[u8], max_len: usize) -> Result<&str, Utf8Error> {
+    from_utf8(&input[..floor_char_boundary(input, max_len)])
+}
+
+/// Skips leading continuation bytes and validates the rest of the input.
+///
+/// Use this when starting to read a UTF-8 stream at an arbitrary offset, which might be in the
+/// middle of a char. The number of skipped bytes is the difference of the lengths of the input and
+/// the returned `str`.
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the rest of the input is not valid UTF-8. More than three
+/// leading continuation bytes are always invalid.
+#[inline]
+pub fn resync(input: &[u8]) -> Result<&str, Utf8Error> {
+    let start = ceil_char_boundary(input, 0);
+    from_utf8(&input[start..]).map_err(|err| err.offset_by(start))
+}
+
+/// Validates the input and splits it into pieces of at most `max_len` bytes each without
+/// splitting a char.
+///
+/// The returned iterator yields pieces as long as possible, so only the last piece may be shorter
+/// than `max_len - 3` bytes.
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the input is not valid UTF-8.
+///
+/// # Panics
+/// If `max_len` is smaller than four, the maximum length of a char.
+#[inline]
+pub fn pieces(input: &[u8], max_len: usize) -> Result<Pieces<'_>, Utf8Error> {
+    assert!(
+        max_len > MAX_CONTINUATION_BYTES,
+        "max_len must be at least four"
+    );
+    Ok(Pieces {
+        rest: from_utf8(input)?,
+        max_len,
+    })
+}
+
+/// Iterator over the pieces of a `str` returned by [`pieces()`].
+#[derive(Clone, Debug)]
+pub struct Pieces<'a> {
+    rest: &'a str,
+    max_len: usize,
+}
+
+impl<'a> Iterator for Pieces<'a> {
+    type Item = &'a str;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let bytes = self.rest.as_bytes();
+        let end = floor_char_boundary(bytes, self.max_len);
+        // SAFETY: rest is valid UTF-8 and end is a char boundary
+        let (piece, rest) = unsafe {
+            (
+                from_utf8_unchecked(bytes.get_unchecked(..end)),
+                from_utf8_unchecked(bytes.get_unchecked(end..)),
+            )
+        };
+        self.rest = rest;
+        Some(piece)
+    }
+}
+
+impl FusedIterator for Pieces<'_> {}
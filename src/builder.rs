@@ -0,0 +1,138 @@
+//! The `builder` module assembles UTF-8 strings from byte fragments.
+//!
+//! [`Utf8StringBuilder`] validates the fragments as they are appended. Chars may be split across
+//! fragments. The finished [`String`] is created without validating the whole buffer again:
+//!
+//! ```rust
+//! use simdutf8::builder::Utf8StringBuilder;
+//!
+//! let mut builder = Utf8StringBuilder::new();
+//! builder.push_bytes(b"Gr\xC3").unwrap();
+//! builder.push_bytes(b"\xBC\xC3\x9Fe").unwrap();
+//! assert_eq!(builder.into_string().unwrap(), "Grüße");
+//!
+//! let mut builder = Utf8StringBuilder::new();
+//! builder.push_bytes(b"abc").unwrap();
+//! let err = builder.push_bytes(b"d\xFF").unwrap_err();
+//! // the offset is relative to the start of the string
+//! assert_eq!(err.valid_up_to(), 4);
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::io;
+
+use crate::compat::Utf8Error;
+use crate::implementation::stream::Utf8Stream;
+
+/// Builds a [`String`] from byte fragments which are validated as they are appended.
+///
+/// Invalid fragments are rejected and leave the builder unchanged, so appending can continue with
+/// other data.
+#[derive(Clone, Default)]
+pub struct Utf8StringBuilder {
+    buffer: Vec<u8>,
+    stream: Utf8Stream,
+}
+
+impl Utf8StringBuilder {
+    /// Creates a new, empty builder.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new, empty builder with at least the specified capacity.
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buffer: Vec::with_capacity(capacity),
+            stream: Utf8Stream::new(),
+        }
+    }
+
+    /// Validates and appends bytes.
+    ///
+    /// The bytes may end with an incomplete char, which must be completed by the next bytes.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if the bytes are not valid UTF-8 after the bytes appended so
+    /// far. The error offsets are relative to the start of the string. The bytes are not appended
+    /// in that case.
+    #[inline]
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Result<(), Utf8Error> {
+        let mut stream = self.stream.clone();
+        stream.update(bytes)?;
+        self.stream = stream;
+        self.buffer.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Validates and appends bytes.
+    ///
+    /// Same as [`push_bytes()`](Self::push_bytes).
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if the bytes are not valid UTF-8 after the bytes appended so
+    /// far.
+    #[inline]
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<(), Utf8Error> {
+        self.push_bytes(bytes)
+    }
+
+    /// Returns the number of bytes appended so far.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns `true` if no bytes have been appended.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Finishes the string.
+    ///
+    /// The bytes are not validated again.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if the string ends with an incomplete char.
+    #[inline]
+    pub fn into_string(self) -> Result<String, Utf8Error> {
+        self.stream.finish()?;
+        // SAFETY: all bytes were validated when they were appended
+        unsafe { Ok(String::from_utf8_unchecked(self.buffer)) }
+    }
+}
+
+#[flexpect::e(clippy::missing_fields_in_debug)] // the validation state is an internal detail
+impl Debug for Utf8StringBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // the buffer is omitted since it might be large
+        f.debug_struct("Utf8StringBuilder")
+            .field("len", &self.buffer.len())
+            .finish()
+    }
+}
+
+/// Appends the written bytes with [`Utf8StringBuilder::push_bytes()`].
+///
+/// Invalid UTF-8 is reported as [`io::ErrorKind::InvalidData`] error wrapping the
+/// [`Utf8Error`].
+impl io::Write for Utf8StringBuilder {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push_bytes(buf)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
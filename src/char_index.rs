@@ -0,0 +1,136 @@
+//! The `char_index` module maps between char indices and byte offsets of large UTF-8 strings.
+//!
+//! [`CharIndex`] stores the number of chars before each 64-byte block, counted in the same SIMD
+//! pass which validates the input. A conversion looks up the block and scans at most 64 bytes
+//! instead of iterating over all chars before the position as with `char_indices().nth(n)`:
+//!
+//! ```rust
+//! use simdutf8::char_index::CharIndex;
+//!
+//! let text = "aö€😊".repeat(100);
+//! let index = CharIndex::new(text.as_bytes()).unwrap();
+//! assert_eq!(index.char_count(), 400);
+//! assert_eq!(index.byte_offset(&text, 222), Some(553));
+//! assert_eq!(index.char_index(&text, 553), Some(222));
+//! // not at a char boundary
+//! assert_eq!(index.char_index(&text, 554), None);
+//! ```
+
+use std::fmt::{Debug, Formatter};
+
+use crate::compat::Utf8Error;
+use crate::implementation::helpers::{block_count, is_continuation_byte, SIMD_CHUNK_SIZE};
+
+/// Number of chars before each 64-byte block of valid UTF-8 text.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CharIndex {
+    /// Number of chars before each block, followed by the number of chars of the text.
+    block_chars: Vec<usize>,
+    /// Length of the text in bytes.
+    len: usize,
+}
+
+impl CharIndex {
+    /// Validates the input and builds its char index in the same pass.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if the input is not valid UTF-8.
+    #[inline]
+    pub fn new(input: &[u8]) -> Result<Self, Utf8Error> {
+        let blocks = block_count(input.len());
+        let mut block_chars = vec![0; blocks + 1];
+        let chars =
+            crate::implementation::validate_with_block_chars(input, &mut block_chars[..blocks])?;
+        block_chars[blocks] = chars;
+        Ok(Self {
+            block_chars,
+            len: input.len(),
+        })
+    }
+
+    /// Returns the length of the indexed text in bytes.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the indexed text is empty.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of chars of the indexed text.
+    #[inline]
+    #[must_use]
+    pub fn char_count(&self) -> usize {
+        // the counts always end with the one of the whole text
+        if let Some(&chars) = self.block_chars.last() {
+            chars
+        } else {
+            0
+        }
+    }
+
+    /// Returns the byte offset of the char with index `char_index` in `text`.
+    ///
+    /// `text` must be the indexed text. The char count maps to the length of the text. Returns
+    /// `None` if the index is greater than the char count.
+    #[inline]
+    #[must_use]
+    pub fn byte_offset(&self, text: &str, char_index: usize) -> Option<usize> {
+        if char_index > self.char_count() {
+            return None;
+        }
+        if char_index == self.char_count() {
+            return Some(self.len);
+        }
+        // each block contains at least one char start, so the counts are strictly increasing
+        let block = match self.block_chars.binary_search(&char_index) {
+            Ok(block) => block,
+            Err(next) => next - 1,
+        };
+        let start = block * SIMD_CHUNK_SIZE;
+        let mut remaining = char_index - self.block_chars[block];
+        for (i, &b) in text.as_bytes().get(start..)?.iter().enumerate() {
+            if !is_continuation_byte(b) {
+                if remaining == 0 {
+                    return Some(start + i);
+                }
+                remaining -= 1;
+            }
+        }
+        None
+    }
+
+    /// Returns the index of the char starting at byte offset `offset` in `text`.
+    ///
+    /// `text` must be the indexed text. The length of the text maps to the char count. Returns
+    /// `None` if the offset is after the end of the text or not at a char boundary.
+    #[inline]
+    #[must_use]
+    pub fn char_index(&self, text: &str, offset: usize) -> Option<usize> {
+        if offset > self.len || !text.is_char_boundary(offset) {
+            return None;
+        }
+        let block = offset / SIMD_CHUNK_SIZE;
+        let start = block * SIMD_CHUNK_SIZE;
+        let chars = text.as_bytes()[start..offset]
+            .iter()
+            .filter(|&&b| !is_continuation_byte(b))
+            .count();
+        Some(self.block_chars[block] + chars)
+    }
+}
+
+#[flexpect::e(clippy::missing_fields_in_debug)] // the block counts might be many
+impl Debug for CharIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CharIndex")
+            .field("len", &self.len)
+            .field("char_count", &self.char_count())
+            .finish()
+    }
+}
@@ -11,9 +11,16 @@
 
 use core::fmt::Display;
 use core::fmt::Formatter;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+use core::slice;
 
 use core::str::{from_utf8_unchecked, from_utf8_unchecked_mut};
 
+#[cfg(all(feature = "std", scoped_threads))]
+use crate::implementation::helpers::is_continuation_byte;
+use crate::implementation::helpers::{get_compat_error, SIMD_CHUNK_SIZE};
+use crate::implementation::stream::Utf8Stream;
 use crate::implementation::validate_utf8_compat;
 
 /// UTF-8 error information compatible with [`std::str::Utf8Error`].
@@ -44,6 +51,15 @@ impl Utf8Error {
     pub fn error_len(&self) -> Option<usize> {
         self.error_len.map(|len| len as usize)
     }
+
+    /// Moves the error by `offset` bytes, used if a subslice starting at `offset` was validated.
+    #[inline]
+    pub(crate) const fn offset_by(self, offset: usize) -> Self {
+        Self {
+            valid_up_to: self.valid_up_to + offset,
+            error_len: self.error_len,
+        }
+    }
 }
 
 impl Display for Utf8Error {
@@ -99,6 +115,184 @@ pub fn from_utf8_mut(input: &mut [u8]) -> Result<&mut str, Utf8Error> {
     }
 }
 
+/// Validates the input up to the first occurrence of `delim` in the same pass which finds it.
+///
+/// Returns the part before the delimiter as `str` and the position of the delimiter, or the whole
+/// input and `None` if it does not occur. The bytes after the delimiter are not validated, so
+/// line- and frame-based protocols do not have to scan the input twice:
+///
+/// ```rust
+/// use simdutf8::compat::validate_until;
+///
+/// assert_eq!(validate_until(b"Gr\xC3\xBC\xC3\x9Fe\r\n\xFF", b'\r').unwrap(), ("Grüße", Some(7)));
+/// assert_eq!(validate_until(b"no delimiter", b'\n').unwrap(), ("no delimiter", None));
+/// ```
+///
+/// The delimiter should be ASCII, a non-ASCII byte is matched inside multi-byte chars.
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the input before the delimiter is not valid UTF-8.
+#[inline]
+pub fn validate_until(input: &[u8], delim: u8) -> Result<(&str, Option<usize>), Utf8Error> {
+    let end = crate::implementation::validate_until_byte(input, delim)?;
+    // SAFETY: the input up to `end` was validated, `end` is in bounds
+    let valid = unsafe { from_utf8_unchecked(input.get_unchecked(..end)) };
+    Ok((valid, if end < input.len() { Some(end) } else { None }))
+}
+
+/// Validates the concatenation of the passed slices as one UTF-8 string without copying them.
+///
+/// Chars may span slice boundaries. Accepts slices of anything dereferencing to `[u8]`, e.g.
+/// `&[u8]`, [`std::io::IoSlice`] or [`std::io::IoSliceMut`], so it can be used for
+/// scatter-gather buffers or the two halves of a ring buffer.
+///
+/// ```rust
+/// use simdutf8::compat::validate_vectored;
+///
+/// let parts: [&[u8]; 3] = [b"ab\xE2", b"\x82", b"\xACcd\xFF"];
+/// let err = validate_vectored(&parts).unwrap_err();
+/// assert_eq!(err.valid_up_to(), 7);
+/// ```
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) on if the concatenated input contains invalid UTF-8. The error
+/// offsets are relative to the start of the concatenated input.
+#[inline]
+pub fn validate_vectored<S: Deref<Target = [u8]>>(slices: &[S]) -> Result<(), Utf8Error> {
+    let mut stream = Utf8Stream::new();
+    for slice in slices {
+        stream.update(slice)?;
+    }
+    stream.finish()
+}
+
+/// Copies `src` to the start of `dst` and validates it in a single pass.
+///
+/// Every byte of `src` is read only once and the validated bytes are the ones which are written
+/// to `dst`. Use this for memory which might be modified concurrently, such as shared memory or
+/// memory-mapped files, to obtain a private copy which is guaranteed to be valid UTF-8.
+///
+/// ```rust
+/// use core::mem::MaybeUninit;
+/// use simdutf8::compat::validate_and_copy;
+///
+/// let mut buffer = [MaybeUninit::<u8>::uninit(); 16];
+/// assert_eq!(validate_and_copy("Grüße".as_bytes(), &mut buffer).unwrap(), "Grüße");
+/// ```
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) on if the input contains invalid UTF-8 with
+/// detailed error information. The contents of `dst` are unspecified in that case.
+///
+/// # Panics
+/// If `dst` is shorter than `src`.
+#[inline]
+pub fn validate_and_copy<'a>(
+    src: &[u8],
+    dst: &'a mut [MaybeUninit<u8>],
+) -> Result<&'a str, Utf8Error> {
+    let len = src.len();
+    let res = crate::implementation::validate_and_copy(src, dst);
+    // SAFETY: the bytes up to `len` are initialized on success and the bytes up to the end of the
+    // failing block on error
+    unsafe {
+        match res {
+            Ok(()) => Ok(from_utf8_unchecked(slice::from_raw_parts(
+                dst.as_ptr().cast::<u8>(),
+                len,
+            ))),
+            Err(pos) => {
+                let copied = core::cmp::min(pos + SIMD_CHUNK_SIZE, len);
+                let copy = slice::from_raw_parts(dst.as_ptr().cast::<u8>(), copied);
+                Err(get_compat_error(copy, pos))
+            }
+        }
+    }
+}
+
+/// Minimum number of bytes validated by each thread of [`validate_parallel()`].
+#[cfg(all(feature = "std", scoped_threads))]
+const PARALLEL_MIN_SEGMENT_LEN: usize = 64 * 1024;
+
+/// Validates the input on up to `threads` threads, including the calling one.
+///
+/// The input is split into segments at char boundaries which are validated concurrently. The
+/// result is exactly the same as the one of [`from_utf8()`], which is used if `threads` is zero or
+/// one or if the input is too short to be worth splitting. Requires Rust 1.63 or later.
+///
+/// ```rust
+/// use simdutf8::compat::validate_parallel;
+///
+/// let mut input = "Grüße, Jürgen ❤".repeat(100_000).into_bytes();
+/// assert!(validate_parallel(&input, 4).is_ok());
+/// input[1_000_000] = 0xFF;
+/// let err = validate_parallel(&input, 4).unwrap_err();
+/// assert_eq!(err.valid_up_to(), 1_000_000);
+/// ```
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) on if the input contains invalid UTF-8 with
+/// detailed error information.
+#[cfg(all(feature = "std", scoped_threads))]
+#[inline]
+pub fn validate_parallel(input: &[u8], threads: usize) -> Result<&str, Utf8Error> {
+    let segments = core::cmp::min(threads, input.len() / PARALLEL_MIN_SEGMENT_LEN);
+    if segments <= 1 {
+        return from_utf8(input);
+    }
+    let mut bounds = Vec::with_capacity(segments + 1);
+    bounds.push(0);
+    for i in 1..segments {
+        let start = input.len() / segments * i;
+        // splitting before a continuation byte would change the result, if there is no char
+        // boundary nearby the segments are merged
+        if let Some(pos) = input[start..start + 4]
+            .iter()
+            .position(|&b| !is_continuation_byte(b))
+        {
+            bounds.push(start + pos);
+        }
+    }
+    bounds.push(input.len());
+
+    let results = std::thread::scope(|scope| {
+        let handles: Vec<_> = bounds[1..]
+            .windows(2)
+            .map(|w| scope.spawn(move || from_utf8(&input[w[0]..w[1]]).err()))
+            .collect();
+        let mut results = Vec::with_capacity(bounds.len() - 1);
+        results.push(from_utf8(&input[..bounds[1]]).err());
+        for handle in handles {
+            results.push(
+                handle
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err)),
+            );
+        }
+        results
+    });
+
+    let first_error = results
+        .iter()
+        .enumerate()
+        .find_map(|(i, res)| res.map(|err| (i, err)));
+    let (i, err) = match first_error {
+        // SAFETY: the concatenation of valid UTF-8 segments is valid UTF-8
+        None => return unsafe { Ok(from_utf8_unchecked(input)) },
+        Some((i, err)) => (i, err.offset_by(bounds[i])),
+    };
+    let end = bounds[i + 1];
+    if err.error_len.is_some() || end == input.len() {
+        return Err(err);
+    }
+    // an incomplete char at the end of the segment is followed by a char boundary, so the error
+    // length has to be determined with it
+    let pos = err.valid_up_to;
+    Err(from_utf8(&input[pos..=end])
+        .err()
+        .map_or(err, |err| err.offset_by(pos)))
+}
+
 /// Allows direct access to the platform-specific unsafe validation implementations.
 #[cfg(feature = "public_imp")]
 pub mod imp {
@@ -0,0 +1,64 @@
+//! The `cstr` module validates NUL-terminated C strings.
+//!
+//! [`until_nul()`] finds the first NUL byte and validates the UTF-8 before it in the same SIMD
+//! pass, so fixed-size C buffers and strings crossing an FFI boundary do not have to be scanned
+//! twice:
+//!
+//! ```rust
+//! use simdutf8::cstr::until_nul;
+//!
+//! assert_eq!(until_nul(b"Gr\xC3\xBC\xC3\x9Fe\0\0\0\0").unwrap(), "Grüße");
+//! // the bytes after the NUL are not validated
+//! assert_eq!(until_nul(b"abc\0\xFF").unwrap(), "abc");
+//! assert_eq!(until_nul(b"no NUL").unwrap(), "no NUL");
+//! ```
+
+#[cfg(feature = "std")]
+use std::ffi::CStr;
+#[cfg(feature = "std")]
+use std::os::raw::c_char;
+
+use crate::compat::Utf8Error;
+
+/// Validates the input up to the first NUL byte, or all of it if there is none.
+///
+/// Returns the part before the NUL byte as `str`. The bytes after it are not validated.
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the input before the first NUL byte is not valid UTF-8.
+#[inline]
+pub fn until_nul(input: &[u8]) -> Result<&str, Utf8Error> {
+    crate::compat::validate_until(input, 0).map(|(valid, _)| valid)
+}
+
+/// Validates a [`CStr`] and returns it as `str` without the terminating NUL byte.
+///
+/// Analogue to [`CStr::to_str()`].
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the C string is not valid UTF-8.
+#[cfg(feature = "std")]
+#[inline]
+pub fn from_cstr(input: &CStr) -> Result<&str, Utf8Error> {
+    crate::compat::from_utf8(input.to_bytes())
+}
+
+/// Validates the NUL-terminated C string at `ptr` and returns it as `str` without the terminating
+/// NUL byte.
+///
+/// Analogue to `CStr::from_ptr(ptr).to_str()`. The length of the string is determined with
+/// [`CStr::from_ptr()`] since reading past the terminating NUL byte, as a vectorized scan would,
+/// is undefined behavior in Rust. Use [`until_nul()`] to find the NUL byte and validate in a
+/// single pass if the size of the buffer is known.
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the C string is not valid UTF-8.
+///
+/// # Safety
+/// The same requirements as for [`CStr::from_ptr()`] apply: `ptr` must point to a NUL-terminated
+/// string which is valid for the returned lifetime and not modified during it.
+#[cfg(feature = "std")]
+#[inline]
+pub unsafe fn from_ptr<'a>(ptr: *const c_char) -> Result<&'a str, Utf8Error> {
+    from_cstr(CStr::from_ptr(ptr))
+}
@@ -0,0 +1,163 @@
+//! The `diagnostics` module renders human-readable reports for [`compat::Utf8Error`](crate::compat::Utf8Error)s.
+//!
+//! A [`Report`] locates the error by line, column and char index and shows a hex dump of the
+//! offending bytes with some surrounding context and a caret underneath, similar to compiler
+//! diagnostics:
+//!
+//! ```rust
+//! use simdutf8::compat::from_utf8;
+//! use simdutf8::diagnostics::Report;
+//!
+//! let input = b"first line\nsecond \xFF line";
+//! let err = from_utf8(input).unwrap_err();
+//! let report = Report::new(input, err);
+//! assert_eq!((report.line(), report.column(), report.char_index()), (2, 8, 18));
+//! assert_eq!(
+//!     report.to_string(),
+//!     "error: invalid utf-8 sequence of 1 bytes from index 18\n \
+//!      --> line 2, column 8, char index 18\n  \
+//!      |\n  \
+//!      | 0000000a: 0a 73 65 63 6f 6e 64 20 ff 20 6c 69 6e 65  .second . line\n  \
+//!      |                                   ^^"
+//! );
+//! ```
+//!
+//! Lines are separated by `\n`, columns are counted in chars starting at one. The line and
+//! column are determined with the SIMD implementation over the valid prefix of the input.
+
+use core::fmt::{Debug, Display, Formatter};
+
+use crate::compat::Utf8Error;
+use crate::implementation::{count_byte, count_chars, rfind_byte};
+
+/// Number of bytes shown before and after the offending bytes in the hex dump.
+const CONTEXT_BYTES: usize = 8;
+
+/// Width of the offset column of the hex dump including the separator.
+const OFFSET_WIDTH: usize = 10;
+
+/// Human-readable report of a [`compat::Utf8Error`](crate::compat::Utf8Error).
+///
+/// The location of the error is computed when the report is created. Use the [`Display`]
+/// implementation to render the full report.
+#[derive(Clone)]
+pub struct Report<'a> {
+    input: &'a [u8],
+    error: Utf8Error,
+    line: usize,
+    column: usize,
+    char_index: usize,
+}
+
+impl<'a> Report<'a> {
+    /// Creates a report for `error`, which must have been returned for `input`.
+    ///
+    /// # Panics
+    /// If [`Utf8Error::valid_up_to()`] is larger than the length of `input`.
+    #[must_use]
+    pub fn new(input: &'a [u8], error: Utf8Error) -> Self {
+        let valid = &input[..error.valid_up_to()];
+        let line_start = rfind_byte(valid, b'\n').map_or(0, |pos| pos + 1);
+        let column_chars = count_chars(&valid[line_start..]);
+        Self {
+            input,
+            error,
+            line: count_byte(&valid[..line_start], b'\n') + 1,
+            column: column_chars + 1,
+            char_index: count_chars(&valid[..line_start]) + column_chars,
+        }
+    }
+
+    /// The reported error.
+    #[inline]
+    #[must_use]
+    pub fn error(&self) -> Utf8Error {
+        self.error
+    }
+
+    /// The line of the error, starting at one.
+    #[inline]
+    #[must_use]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the error in chars, starting at one.
+    #[inline]
+    #[must_use]
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The number of chars preceding the error.
+    #[inline]
+    #[must_use]
+    pub fn char_index(&self) -> usize {
+        self.char_index
+    }
+
+    /// The invalid byte sequence, or the incomplete sequence at the end of the input if
+    /// [`Utf8Error::error_len()`] is `None`.
+    #[inline]
+    #[must_use]
+    pub fn invalid_bytes(&self) -> &'a [u8] {
+        let start = self.error.valid_up_to();
+        let end = self
+            .error
+            .error_len()
+            .map_or(self.input.len(), |len| start + len);
+        &self.input[start..core::cmp::min(end, self.input.len())]
+    }
+}
+
+impl Debug for Report<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        // the input is omitted since it might be large
+        f.debug_struct("Report")
+            .field("error", &self.error)
+            .field("line", &self.line)
+            .field("column", &self.column)
+            .field("char_index", &self.char_index)
+            .finish()
+    }
+}
+
+impl Display for Report<'_> {
+    #[flexpect::e(clippy::uninlined_format_args)] // inlined format args require Rust 1.58
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let error_start = self.error.valid_up_to();
+        let error_end = error_start + self.invalid_bytes().len();
+        let start = error_start.saturating_sub(CONTEXT_BYTES);
+        let end = core::cmp::min(error_end + CONTEXT_BYTES, self.input.len());
+        let dump = &self.input[start..end];
+
+        writeln!(f, "error: {}", self.error)?;
+        writeln!(
+            f,
+            " --> line {}, column {}, char index {}",
+            self.line, self.column, self.char_index
+        )?;
+        writeln!(f, "  |")?;
+        write!(f, "  | {:08x}:", start)?;
+        for byte in dump {
+            write!(f, " {:02x}", byte)?;
+        }
+        f.write_str("  ")?;
+        for &byte in dump {
+            let c = if byte == b' ' || byte.is_ascii_graphic() {
+                byte as char
+            } else {
+                '.'
+            };
+            write!(f, "{}", c)?;
+        }
+        write!(
+            f,
+            "\n  | {:indent$}{:^<carets$}",
+            "",
+            "",
+            indent = OFFSET_WIDTH + 3 * (error_start - start),
+            carets = (3 * (error_end - error_start)).saturating_sub(1)
+        )
+    }
+}
@@ -38,3 +38,15 @@ unsafe fn validate_utf8_compat_neon(input: &[u8]) -> Result<(), crate::compat::U
 
 #[cfg(not(target_feature = "neon"))]
 pub(crate) use super::validate_utf8_compat_fallback as validate_utf8_compat;
+
+#[inline]
+#[cfg(target_feature = "neon")]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    &neon::KERNELS
+}
+
+#[inline]
+#[cfg(not(target_feature = "neon"))]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    &super::fallback::KERNELS
+}
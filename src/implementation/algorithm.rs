@@ -337,12 +337,611 @@ macro_rules! algorithm_simd {
             }
         }
 
+        $(#[$feat])*
+        #[inline]
+        unsafe fn count_chars(input: &[u8]) -> usize {
+            use crate::implementation::helpers::{partial_block_mask, SIMD_CHUNK_SIZE};
+            let len = input.len();
+            let mut continuations: usize = 0;
+            let mut idx: usize = 0;
+            let iter_lim = len - (len % SIMD_CHUNK_SIZE);
+
+            while idx < iter_lim {
+                let simd_input = SimdInput::new(input.as_ptr().add(idx));
+                if !simd_input.is_ascii() {
+                    continuations += simd_input.continuation_mask().count_ones() as usize;
+                }
+                idx += SIMD_CHUNK_SIZE;
+            }
+            if idx < len {
+                let simd_input = SimdInput::new_partial(input.as_ptr().add(idx), len - idx);
+                let mask = simd_input.continuation_mask() & partial_block_mask(len - idx);
+                continuations += mask.count_ones() as usize;
+            }
+            len - continuations
+        }
+
+        $(#[$feat])*
+        #[inline]
+        unsafe fn count_byte(input: &[u8], byte: u8) -> usize {
+            use crate::implementation::helpers::{partial_block_mask, SIMD_CHUNK_SIZE};
+            let len = input.len();
+            let mut count: usize = 0;
+            let mut idx: usize = 0;
+            let iter_lim = len - (len % SIMD_CHUNK_SIZE);
+
+            while idx < iter_lim {
+                let simd_input = SimdInput::new(input.as_ptr().add(idx));
+                count += simd_input.eq_mask(byte).count_ones() as usize;
+                idx += SIMD_CHUNK_SIZE;
+            }
+            if idx < len {
+                let simd_input = SimdInput::new_partial(input.as_ptr().add(idx), len - idx);
+                let mask = simd_input.eq_mask(byte) & partial_block_mask(len - idx);
+                count += mask.count_ones() as usize;
+            }
+            count
+        }
+
+        $(#[$feat])*
+        #[inline]
+        unsafe fn rfind_byte(input: &[u8], byte: u8) -> Option<usize> {
+            use crate::implementation::helpers::{partial_block_mask, SIMD_CHUNK_SIZE};
+            let len = input.len();
+            let mut idx = len - (len % SIMD_CHUNK_SIZE);
+
+            if idx < len {
+                let simd_input = SimdInput::new_partial(input.as_ptr().add(idx), len - idx);
+                let mask = simd_input.eq_mask(byte) & partial_block_mask(len - idx);
+                if mask != 0 {
+                    return Some(idx + 63 - mask.leading_zeros() as usize);
+                }
+            }
+            while idx > 0 {
+                idx -= SIMD_CHUNK_SIZE;
+                let mask = SimdInput::new(input.as_ptr().add(idx)).eq_mask(byte);
+                if mask != 0 {
+                    return Some(idx + 63 - mask.leading_zeros() as usize);
+                }
+            }
+            None
+        }
+
+        $(#[$feat])*
+        #[inline]
+        unsafe fn first_non_ascii(input: &[u8]) -> Option<usize> {
+            use crate::implementation::helpers::SIMD_CHUNK_SIZE;
+            let len = input.len();
+            let mut idx: usize = 0;
+            let iter_lim = len - (len % SIMD_CHUNK_SIZE);
+
+            while idx < iter_lim {
+                let simd_input = SimdInput::new(input.as_ptr().add(idx));
+                if !simd_input.is_ascii() {
+                    return Some(idx + simd_input.non_ascii_mask().trailing_zeros() as usize);
+                }
+                idx += SIMD_CHUNK_SIZE;
+            }
+            if idx < len {
+                // padding bytes are zero and thus ASCII
+                let mask = SimdInput::new_partial(input.as_ptr().add(idx), len - idx).non_ascii_mask();
+                if mask != 0 {
+                    return Some(idx + mask.trailing_zeros() as usize);
+                }
+            }
+            None
+        }
+
+        /// Validates each of `inputs` and stores the results in `results`.
+        ///
+        /// # Safety
+        /// `results` must have the same length as `inputs`.
+        $(#[$feat])*
+        #[inline]
+        unsafe fn validate_many(inputs: &[&[u8]], results: &mut [bool]) {
+            use crate::implementation::helpers::{
+                is_continuation_byte, memcpy_unaligned_nonoverlapping_inline_opt_lt_64,
+                SIMD_CHUNK_SIZE,
+            };
+            let mut idx: usize = 0;
+            while idx < inputs.len() {
+                let input = *inputs.get_unchecked(idx);
+                if input.len() >= SIMD_CHUNK_SIZE {
+                    // this implementation is already selected, no need to dispatch again
+                    *results.get_unchecked_mut(idx) = validate_utf8_basic(input).is_ok();
+                    idx += 1;
+                    continue;
+                }
+                // Pack as many short inputs into one block as possible. If the block is valid and
+                // no input starts with a continuation byte, every input ends on a char boundary
+                // of the block and is thus valid by itself.
+                let mut block = TempSimdChunk::new();
+                let mut len: usize = 0;
+                let mut starts_ok = true;
+                let group_start = idx;
+                while idx < inputs.len() && inputs.get_unchecked(idx).len() <= SIMD_CHUNK_SIZE - len {
+                    let input = *inputs.get_unchecked(idx);
+                    if let Some(&first) = input.first() {
+                        starts_ok &= !is_continuation_byte(first);
+                    }
+                    memcpy_unaligned_nonoverlapping_inline_opt_lt_64(
+                        input.as_ptr(),
+                        block.0.as_mut_ptr().add(len),
+                        input.len(),
+                    );
+                    len += input.len();
+                    idx += 1;
+                }
+                let mut algorithm = Utf8CheckAlgorithm::<SimdU8Value>::default();
+                algorithm.check_utf8(SimdInput::new(block.0.as_ptr()));
+                algorithm.check_incomplete_pending();
+                let group_ok = starts_ok && !algorithm.has_error();
+                for i in group_start..idx {
+                    // short inputs are validated with std like in `basic::from_utf8()`
+                    *results.get_unchecked_mut(i) =
+                        group_ok || core::str::from_utf8(inputs.get_unchecked(i)).is_ok();
+                }
+            }
+        }
+
+        /// Copies `src` to `dst` while validating it, reading every byte of `src` only once.
+        ///
+        /// Returns `Err(idx)` with the start of the block in which an error was found. The
+        /// bytes up to the end of that block have been copied to `dst`.
+        ///
+        /// # Safety
+        /// `dst` must have at least the length of `src`.
+        $(#[$feat])*
+        #[inline]
+        unsafe fn validate_and_copy(
+            src: &[u8],
+            dst: &mut [core::mem::MaybeUninit<u8>],
+        ) -> core::result::Result<(), usize> {
+            use crate::implementation::helpers::{
+                memcpy_unaligned_nonoverlapping_inline_opt_lt_64, SIMD_CHUNK_SIZE,
+            };
+            let len = src.len();
+            let dst = dst.as_mut_ptr().cast::<u8>();
+            let mut algorithm = Utf8CheckAlgorithm::<SimdU8Value>::default();
+            let mut idx: usize = 0;
+            let iter_lim = len - (len % SIMD_CHUNK_SIZE);
+
+            while idx < iter_lim {
+                if PREFETCH {
+                    simd_prefetch(src.as_ptr().add(idx + SIMD_CHUNK_SIZE * 2));
+                }
+                let simd_input = SimdInput::new(src.as_ptr().add(idx));
+                simd_input.store(dst.add(idx));
+                algorithm.check_utf8(simd_input);
+                if algorithm.has_error() {
+                    return Err(idx);
+                }
+                idx += SIMD_CHUNK_SIZE;
+            }
+            if idx < len {
+                // the remaining bytes are copied from the block which was validated
+                let simd_input = SimdInput::new_partial(src.as_ptr().add(idx), len - idx);
+                let mut tmpbuf = TempSimdChunk::new();
+                simd_input.store(tmpbuf.0.as_mut_ptr());
+                memcpy_unaligned_nonoverlapping_inline_opt_lt_64(
+                    tmpbuf.0.as_ptr(),
+                    dst.add(idx),
+                    len - idx,
+                );
+                algorithm.check_utf8(simd_input);
+            }
+            algorithm.check_incomplete_pending();
+            if algorithm.has_error() {
+                Err(idx)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Finds the first occurrence of `byte` and validates the input before it in the same
+        /// pass.
+        ///
+        /// Returns `Ok(end)` with the position of `byte` or the input length if it does not
+        /// occur. Returns `Err(idx)` with the start of the block in which an error was found.
+        $(#[$feat])*
+        #[inline]
+        unsafe fn validate_until_byte(input: &[u8], byte: u8) -> core::result::Result<usize, usize> {
+            use crate::implementation::helpers::{partial_block_mask, SIMD_CHUNK_SIZE};
+            let len = input.len();
+            let mut algorithm = Utf8CheckAlgorithm::<SimdU8Value>::default();
+            let mut idx: usize = 0;
+            let mut end = len;
+            let iter_lim = len - (len % SIMD_CHUNK_SIZE);
+
+            while idx < iter_lim {
+                let simd_input = SimdInput::new(input.as_ptr().add(idx));
+                let mask = simd_input.eq_mask(byte);
+                if mask != 0 {
+                    end = idx + mask.trailing_zeros() as usize;
+                    break;
+                }
+                algorithm.check_utf8(simd_input);
+                if algorithm.has_error() {
+                    return Err(idx);
+                }
+                idx += SIMD_CHUNK_SIZE;
+            }
+            if end == len && idx < len {
+                let simd_input = SimdInput::new_partial(input.as_ptr().add(idx), len - idx);
+                let mask = simd_input.eq_mask(byte) & partial_block_mask(len - idx);
+                if mask != 0 {
+                    end = idx + mask.trailing_zeros() as usize;
+                }
+            }
+            if idx < end {
+                // validate the part of the last block before `byte`
+                algorithm.check_utf8(SimdInput::new_partial(input.as_ptr().add(idx), end - idx));
+            }
+            algorithm.check_incomplete_pending();
+            if algorithm.has_error() {
+                Err(idx)
+            } else {
+                Ok(end)
+            }
+        }
+
+        /// Validates the input and adds its byte classes to `stats` in the same pass.
+        ///
+        /// Returns `Err(idx)` with the start of the block in which an error was found. `stats`
+        /// is unspecified in that case.
+        $(#[$feat])*
+        #[inline]
+        unsafe fn validate_with_stats(
+            input: &[u8],
+            stats: &mut crate::stats::Utf8Stats,
+        ) -> core::result::Result<(), usize> {
+            use crate::implementation::helpers::{partial_block_mask, SIMD_CHUNK_SIZE};
+            let len = input.len();
+            let mut algorithm = Utf8CheckAlgorithm::<SimdU8Value>::default();
+            let mut idx: usize = 0;
+            let mut block_start: usize = 0;
+
+            // the last block is handled in the loop as well so that the counting is not
+            // duplicated
+            while idx < len {
+                block_start = idx;
+                let (simd_input, mask) = if len - idx >= SIMD_CHUNK_SIZE {
+                    (SimdInput::new(input.as_ptr().add(idx)), !0_u64)
+                } else {
+                    (
+                        SimdInput::new_partial(input.as_ptr().add(idx), len - idx),
+                        partial_block_mask(len - idx),
+                    )
+                };
+                stats.newlines += (simd_input.eq_mask(b'\n') & mask).count_ones() as usize;
+                if simd_input.is_ascii() {
+                    stats.ascii += mask.count_ones() as usize;
+                    algorithm.check_incomplete_pending();
+                } else {
+                    // padding bytes are zero and thus not counted as lead bytes
+                    stats.ascii += (!simd_input.non_ascii_mask() & mask).count_ones() as usize;
+                    stats.lead2 += simd_input.top_bits_mask(0b1100_0000).count_ones() as usize;
+                    stats.lead3 += simd_input.top_bits_mask(0b1110_0000).count_ones() as usize;
+                    stats.lead4 += simd_input.top_bits_mask(0b1111_0000).count_ones() as usize;
+                    algorithm.check_block(simd_input);
+                }
+                if algorithm.has_error() {
+                    return Err(block_start);
+                }
+                idx += SIMD_CHUNK_SIZE;
+            }
+            algorithm.check_incomplete_pending();
+            if algorithm.has_error() {
+                Err(block_start)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Validates the input and passes the positions of `byte` to `sink` in the same pass.
+        ///
+        /// `sink` is called with the start of each block containing `byte` and the mask of its
+        /// occurrences. Returns `Err(idx)` with the start of the block in which an error was
+        /// found. `sink` may have been called for blocks after the error in that case.
+        $(#[$feat])*
+        #[inline]
+        unsafe fn validate_with_byte_masks(
+            input: &[u8],
+            byte: u8,
+            sink: &mut dyn FnMut(usize, u64),
+        ) -> core::result::Result<(), usize> {
+            use crate::implementation::helpers::{partial_block_mask, SIMD_CHUNK_SIZE};
+            let len = input.len();
+            let mut algorithm = Utf8CheckAlgorithm::<SimdU8Value>::default();
+            let mut idx: usize = 0;
+            let mut block_start: usize = 0;
+
+            while idx < len {
+                block_start = idx;
+                let (simd_input, mask) = if len - idx >= SIMD_CHUNK_SIZE {
+                    (SimdInput::new(input.as_ptr().add(idx)), !0_u64)
+                } else {
+                    (
+                        SimdInput::new_partial(input.as_ptr().add(idx), len - idx),
+                        partial_block_mask(len - idx),
+                    )
+                };
+                let byte_mask = simd_input.eq_mask(byte) & mask;
+                if byte_mask != 0 {
+                    sink(idx, byte_mask);
+                }
+                if simd_input.is_ascii() {
+                    algorithm.check_incomplete_pending();
+                } else {
+                    algorithm.check_block(simd_input);
+                }
+                if algorithm.has_error() {
+                    return Err(block_start);
+                }
+                idx += SIMD_CHUNK_SIZE;
+            }
+            algorithm.check_incomplete_pending();
+            if algorithm.has_error() {
+                Err(block_start)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Validates the input and stores the number of chars before each block in the same
+        /// pass.
+        ///
+        /// `block_chars` must have an element for each block, including the partial last one.
+        /// Returns `Ok(chars)` with the number of chars of the input or `Err(idx)` with the start
+        /// of the block in which an error was found. `block_chars` is unspecified in that case.
+        $(#[$feat])*
+        #[inline]
+        unsafe fn validate_with_block_chars(
+            input: &[u8],
+            block_chars: &mut [usize],
+        ) -> core::result::Result<usize, usize> {
+            use crate::implementation::helpers::{partial_block_mask, SIMD_CHUNK_SIZE};
+            let len = input.len();
+            let mut algorithm = Utf8CheckAlgorithm::<SimdU8Value>::default();
+            let mut idx: usize = 0;
+            let mut block_start: usize = 0;
+            let mut chars: usize = 0;
+
+            for chars_before in block_chars.iter_mut() {
+                if idx >= len {
+                    break;
+                }
+                block_start = idx;
+                *chars_before = chars;
+                if len - idx >= SIMD_CHUNK_SIZE {
+                    let simd_input = SimdInput::new(input.as_ptr().add(idx));
+                    if simd_input.is_ascii() {
+                        chars += SIMD_CHUNK_SIZE;
+                        algorithm.check_incomplete_pending();
+                    } else {
+                        chars += SIMD_CHUNK_SIZE
+                            - simd_input.continuation_mask().count_ones() as usize;
+                        algorithm.check_block(simd_input);
+                    }
+                } else {
+                    let simd_input = SimdInput::new_partial(input.as_ptr().add(idx), len - idx);
+                    let mask = simd_input.continuation_mask() & partial_block_mask(len - idx);
+                    chars += len - idx - mask.count_ones() as usize;
+                    algorithm.check_block(simd_input);
+                }
+                if algorithm.has_error() {
+                    return Err(block_start);
+                }
+                idx += SIMD_CHUNK_SIZE;
+            }
+            algorithm.check_incomplete_pending();
+            if algorithm.has_error() {
+                Err(block_start)
+            } else {
+                Ok(chars)
+            }
+        }
+
+        /// Counts the UTF-16 code units needed to encode valid UTF-8 `input`.
+        ///
+        /// Each char start counts one unit, the lead bytes of 4-byte sequences count another
+        /// one for the surrogate pair.
+        $(#[$feat])*
+        #[inline]
+        unsafe fn count_utf16_units(input: &[u8]) -> usize {
+            use crate::implementation::helpers::{partial_block_mask, SIMD_CHUNK_SIZE};
+            let len = input.len();
+            let mut count: usize = 0;
+            let mut idx: usize = 0;
+
+            while idx < len {
+                let (simd_input, mask) = if len - idx >= SIMD_CHUNK_SIZE {
+                    (SimdInput::new(input.as_ptr().add(idx)), !0_u64)
+                } else {
+                    (
+                        SimdInput::new_partial(input.as_ptr().add(idx), len - idx),
+                        partial_block_mask(len - idx),
+                    )
+                };
+                count += mask.count_ones() as usize;
+                if !simd_input.is_ascii() {
+                    // padding bytes are zero and thus neither continuation nor lead bytes
+                    count -= simd_input.continuation_mask().count_ones() as usize;
+                    count += simd_input.top_bits_mask(0b1111_0000).count_ones() as usize;
+                }
+                idx += SIMD_CHUNK_SIZE;
+            }
+            count
+        }
+
+        /// Skips complete blocks of valid UTF-8 `input` as long as the chars starting in them
+        /// need at most `units` UTF-16 code units in total.
+        ///
+        /// Returns the start of the first block which was not skipped and the number of code
+        /// units of the chars starting before it.
+        $(#[$feat])*
+        #[inline]
+        unsafe fn skip_utf16_units(input: &[u8], units: usize) -> (usize, usize) {
+            use crate::implementation::helpers::SIMD_CHUNK_SIZE;
+            let len = input.len();
+            let mut count: usize = 0;
+            let mut idx: usize = 0;
+            let iter_lim = len - (len % SIMD_CHUNK_SIZE);
+
+            while idx < iter_lim {
+                let simd_input = SimdInput::new(input.as_ptr().add(idx));
+                let block_units = if simd_input.is_ascii() {
+                    SIMD_CHUNK_SIZE
+                } else {
+                    SIMD_CHUNK_SIZE - simd_input.continuation_mask().count_ones() as usize
+                        + simd_input.top_bits_mask(0b1111_0000).count_ones() as usize
+                };
+                if count + block_units > units {
+                    break;
+                }
+                count += block_units;
+                idx += SIMD_CHUNK_SIZE;
+            }
+            (idx, count)
+        }
+
+        /// Computes the char start and error masks of each block of the input.
+        ///
+        /// `prev` holds the last three bytes before the input. `masks` must have an element for
+        /// each block, including the partial last one. An incomplete char at the end of the
+        /// input is not reported.
+        $(#[$feat])*
+        #[inline]
+        unsafe fn block_masks(input: &[u8], prev: [u8; 3], masks: &mut [crate::bitmap::BlockMasks]) {
+            use crate::implementation::helpers::{partial_block_mask, SIMD_CHUNK_SIZE};
+            let len = input.len();
+            let val_len = core::mem::size_of::<SimdU8Value>();
+            let mut prev_chunk = TempSimdChunk::new();
+            prev_chunk.0[val_len - 3] = prev[0];
+            prev_chunk.0[val_len - 2] = prev[1];
+            prev_chunk.0[val_len - 1] = prev[2];
+            let mut prev = SimdU8Value::load_from(prev_chunk.0.as_ptr());
+            let mut errors = TempSimdChunk::new();
+            let mut idx: usize = 0;
+
+            for block_masks in masks.iter_mut() {
+                if idx >= len {
+                    break;
+                }
+                let (simd_input, mask) = if len - idx >= SIMD_CHUNK_SIZE {
+                    (SimdInput::new(input.as_ptr().add(idx)), !0_u64)
+                } else {
+                    (
+                        SimdInput::new_partial(input.as_ptr().add(idx), len - idx),
+                        partial_block_mask(len - idx),
+                    )
+                };
+                block_masks.char_starts = !simd_input.continuation_mask() & mask;
+                if simd_input.is_ascii()
+                    && !Utf8CheckAlgorithm::<SimdU8Value>::is_incomplete(prev).any_bit_set()
+                {
+                    block_masks.errors = 0;
+                } else {
+                    let mut i = 0;
+                    while i < simd_input.vals.len() {
+                        let val = *simd_input.vals.as_ptr().add(i);
+                        let prev1 = val.prev1(prev);
+                        let special_cases =
+                            Utf8CheckAlgorithm::<SimdU8Value>::check_special_cases(val, prev1);
+                        Utf8CheckAlgorithm::<SimdU8Value>::check_multibyte_lengths(
+                            val,
+                            prev,
+                            special_cases,
+                        )
+                        .store_to(errors.0.as_mut_ptr().add(i * val_len));
+                        prev = val;
+                        i += 1;
+                    }
+                    block_masks.errors = !SimdInput::new(errors.0.as_ptr()).eq_mask(0) & mask;
+                }
+                prev = *simd_input.vals.as_ptr().add(simd_input.vals.len() - 1);
+                idx += SIMD_CHUNK_SIZE;
+            }
+        }
+
+        /// Validates the input and stores the masks of the occurrences of each of `bytes` in
+        /// each block in the same pass.
+        ///
+        /// The masks of block `i` are stored at `bitmaps[i * bytes.len()..(i + 1) * bytes.len()]`.
+        /// Returns `Err(idx)` with the start of the block in which an error was found.
+        $(#[$feat])*
+        #[inline]
+        unsafe fn validate_with_byte_bitmaps(
+            input: &[u8],
+            bytes: &[u8],
+            bitmaps: &mut [u64],
+        ) -> core::result::Result<(), usize> {
+            use crate::implementation::helpers::{partial_block_mask, SIMD_CHUNK_SIZE};
+            let len = input.len();
+            let mut algorithm = Utf8CheckAlgorithm::<SimdU8Value>::default();
+            let mut idx: usize = 0;
+            let mut block_start: usize = 0;
+            let mut block_bitmaps = bitmaps.as_mut_ptr();
+
+            while idx < len {
+                block_start = idx;
+                let (simd_input, mask) = if len - idx >= SIMD_CHUNK_SIZE {
+                    (SimdInput::new(input.as_ptr().add(idx)), !0_u64)
+                } else {
+                    (
+                        SimdInput::new_partial(input.as_ptr().add(idx), len - idx),
+                        partial_block_mask(len - idx),
+                    )
+                };
+                for &byte in bytes {
+                    *block_bitmaps = simd_input.eq_mask(byte) & mask;
+                    block_bitmaps = block_bitmaps.add(1);
+                }
+                if simd_input.is_ascii() {
+                    algorithm.check_incomplete_pending();
+                } else {
+                    algorithm.check_block(simd_input);
+                }
+                if algorithm.has_error() {
+                    return Err(block_start);
+                }
+                idx += SIMD_CHUNK_SIZE;
+            }
+            algorithm.check_incomplete_pending();
+            if algorithm.has_error() {
+                Err(block_start)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Auxiliary kernels of this implementation, selected at runtime by
+        /// `crate::implementation::kernels()`.
+        #[allow(dead_code)] // unused if the implementation cannot be auto-selected
+        pub(crate) static KERNELS: crate::implementation::Kernels = crate::implementation::Kernels {
+            count_chars,
+            count_byte,
+            rfind_byte,
+            first_non_ascii,
+            validate_many,
+            validate_and_copy,
+            validate_until_byte,
+            validate_with_stats,
+            validate_with_byte_masks,
+            validate_with_block_chars,
+            count_utf16_units,
+            skip_utf16_units,
+            block_masks,
+            validate_with_byte_bitmaps,
+        };
+
         /// Low-level implementation of the [`basic::imp::Utf8Validator`] trait.
         ///
         /// This is implementation requires CPU SIMD features specified by the module it resides in.
         /// It is undefined behavior to call it if the required CPU features are not
         /// available.
         #[cfg(feature = "public_imp")]
+        #[derive(Clone)]
         pub struct Utf8ValidatorImp {
             algorithm: Utf8CheckAlgorithm<SimdU8Value>,
             incomplete_data: [u8; 64],
@@ -429,12 +1028,61 @@ macro_rules! algorithm_simd {
             }
         }
 
+        #[cfg(feature = "public_imp")]
+        impl basic::imp::Utf8ValidatorSnapshot for Utf8ValidatorImp {
+            $(#[$feat])*
+            #[inline]
+            unsafe fn snapshot(&self) -> basic::imp::Utf8ValidatorState {
+                // only the last three bytes of the previous block are used by the next one
+                let mut prev = TempSimdChunk::new();
+                self.algorithm.prev.store_to(prev.0.as_mut_ptr());
+                let prev_end = core::mem::size_of::<SimdU8Value>();
+                let mut state = basic::imp::Utf8ValidatorState {
+                    error: self.algorithm.has_error(),
+                    prev: [0; 3],
+                    pending: [0; 64],
+                    pending_len: self.incomplete_len,
+                };
+                state.prev.copy_from_slice(&prev.0[prev_end - 3..prev_end]);
+                state.pending[..self.incomplete_len]
+                    .copy_from_slice(&self.incomplete_data[..self.incomplete_len]);
+                state
+            }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn restore(state: &basic::imp::Utf8ValidatorState) -> Self {
+                let mut prev = TempSimdChunk::new();
+                let prev_end = core::mem::size_of::<SimdU8Value>();
+                prev.0[prev_end - 3..prev_end].copy_from_slice(&state.prev);
+                let prev = SimdU8Value::load_from(prev.0.as_ptr());
+                let error = if state.error {
+                    SimdU8Value::splat(0xff)
+                } else {
+                    SimdU8Value::splat0()
+                };
+                let mut incomplete_data = [0; 64];
+                incomplete_data[..state.pending_len]
+                    .copy_from_slice(&state.pending[..state.pending_len]);
+                Self {
+                    algorithm: Utf8CheckAlgorithm {
+                        prev,
+                        incomplete: Utf8CheckAlgorithm::<SimdU8Value>::is_incomplete(prev),
+                        error,
+                    },
+                    incomplete_data,
+                    incomplete_len: state.pending_len,
+                }
+            }
+        }
+
         /// Low-level implementation of the [`basic::imp::ChunkedUtf8Validator`] trait.
         ///
         /// This is implementation requires CPU SIMD features specified by the module it resides in.
         /// It is undefined behavior to call it if the required CPU features are not
         /// available.
         #[cfg(feature = "public_imp")]
+        #[derive(Clone)]
         pub struct ChunkedUtf8ValidatorImp {
             algorithm: Utf8CheckAlgorithm<SimdU8Value>,
         }
@@ -536,6 +1184,15 @@ macro_rules! simd_input_128_bit {
                 Self::new(tmpbuf.0.as_ptr())
             }
 
+            $(#[$feat])*
+            #[inline]
+            unsafe fn store(&self, ptr: *mut u8) {
+                self.vals[0].store_to(ptr);
+                self.vals[1].store_to(ptr.add(16));
+                self.vals[2].store_to(ptr.add(32));
+                self.vals[3].store_to(ptr.add(48));
+            }
+
             $(#[$feat])*
             #[inline]
             unsafe fn is_ascii(&self) -> bool {
@@ -544,6 +1201,46 @@ macro_rules! simd_input_128_bit {
                 let r = r1.or(r2);
                 r.is_ascii()
             }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn non_ascii_mask(&self) -> u64 {
+                self.vals[0].bitmask()
+                    | self.vals[1].bitmask() << 16
+                    | self.vals[2].bitmask() << 32
+                    | self.vals[3].bitmask() << 48
+            }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn eq_mask(&self, byte: u8) -> u64 {
+                let byte = SimdU8Value::splat(byte);
+                self.vals[0].cmp_eq(byte).bitmask()
+                    | self.vals[1].cmp_eq(byte).bitmask() << 16
+                    | self.vals[2].cmp_eq(byte).bitmask() << 32
+                    | self.vals[3].cmp_eq(byte).bitmask() << 48
+            }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn continuation_mask(&self) -> u64 {
+                let top_bits = SimdU8Value::splat(0b1100_0000);
+                let continuation = SimdU8Value::splat(0b1000_0000);
+                self.vals[0].and(top_bits).cmp_eq(continuation).bitmask()
+                    | self.vals[1].and(top_bits).cmp_eq(continuation).bitmask() << 16
+                    | self.vals[2].and(top_bits).cmp_eq(continuation).bitmask() << 32
+                    | self.vals[3].and(top_bits).cmp_eq(continuation).bitmask() << 48
+            }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn top_bits_mask(&self, bits: u8) -> u64 {
+                let bits = SimdU8Value::splat(bits);
+                self.vals[0].and(bits).cmp_eq(bits).bitmask()
+                    | self.vals[1].and(bits).cmp_eq(bits).bitmask() << 16
+                    | self.vals[2].and(bits).cmp_eq(bits).bitmask() << 32
+                    | self.vals[3].and(bits).cmp_eq(bits).bitmask() << 48
+            }
         }
     };
 }
@@ -579,11 +1276,48 @@ macro_rules! simd_input_256_bit {
                 Self::new(tmpbuf.0.as_ptr())
             }
 
+            $(#[$feat])*
+            #[inline]
+            unsafe fn store(&self, ptr: *mut u8) {
+                self.vals[0].store_to(ptr);
+                self.vals[1].store_to(ptr.add(32));
+            }
+
             $(#[$feat])*
             #[inline]
             unsafe fn is_ascii(&self) -> bool {
                 self.vals[0].or(self.vals[1]).is_ascii()
             }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn non_ascii_mask(&self) -> u64 {
+                self.vals[0].bitmask() | self.vals[1].bitmask() << 32
+            }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn eq_mask(&self, byte: u8) -> u64 {
+                let byte = SimdU8Value::splat(byte);
+                self.vals[0].cmp_eq(byte).bitmask() | self.vals[1].cmp_eq(byte).bitmask() << 32
+            }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn continuation_mask(&self) -> u64 {
+                let top_bits = SimdU8Value::splat(0b1100_0000);
+                let continuation = SimdU8Value::splat(0b1000_0000);
+                self.vals[0].and(top_bits).cmp_eq(continuation).bitmask()
+                    | self.vals[1].and(top_bits).cmp_eq(continuation).bitmask() << 32
+            }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn top_bits_mask(&self, bits: u8) -> u64 {
+                let bits = SimdU8Value::splat(bits);
+                self.vals[0].and(bits).cmp_eq(bits).bitmask()
+                    | self.vals[1].and(bits).cmp_eq(bits).bitmask() << 32
+            }
         }
     };
 }
@@ -617,11 +1351,45 @@ macro_rules! simd_input_512_bit {
                 }
             }
 
+            $(#[$feat])*
+            #[inline]
+            unsafe fn store(&self, ptr: *mut u8) {
+                self.vals[0].store_to(ptr);
+            }
+
             $(#[$feat])*
             #[inline]
             unsafe fn is_ascii(&self) -> bool {
                 self.vals[0].is_ascii()
             }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn non_ascii_mask(&self) -> u64 {
+                self.vals[0].bitmask()
+            }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn eq_mask(&self, byte: u8) -> u64 {
+                self.vals[0].cmp_eq(SimdU8Value::splat(byte)).bitmask()
+            }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn continuation_mask(&self) -> u64 {
+                self.vals[0]
+                    .and(SimdU8Value::splat(0b1100_0000))
+                    .cmp_eq(SimdU8Value::splat(0b1000_0000))
+                    .bitmask()
+            }
+
+            $(#[$feat])*
+            #[inline]
+            unsafe fn top_bits_mask(&self, bits: u8) -> u64 {
+                let bits = SimdU8Value::splat(bits);
+                self.vals[0].and(bits).cmp_eq(bits).bitmask()
+            }
         }
     };
 }
@@ -137,3 +137,50 @@ unsafe fn validate_utf8_compat_neon(
     all(not(feature = "std"), not(target_feature = "neon"))
 ))]
 pub(crate) use super::validate_utf8_compat_fallback as validate_utf8_compat;
+
+// kernels() std: implementation auto-selection
+
+#[cfg(all(feature = "armv7_neon", feature = "std", not(target_feature = "neon")))]
+#[inline]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    use core::ptr;
+    use std::sync::atomic::{AtomicPtr, Ordering};
+
+    #[cold]
+    fn get_fastest_available_kernels() -> &'static super::Kernels {
+        if std::arch::is_arm_feature_detected!("neon") {
+            &neon::KERNELS
+        } else {
+            &super::fallback::KERNELS
+        }
+    }
+
+    static KERNELS: AtomicPtr<super::Kernels> = AtomicPtr::new(ptr::null_mut());
+
+    let kernels = KERNELS.load(Ordering::Relaxed);
+    if kernels.is_null() {
+        let fastest = get_fastest_available_kernels();
+        KERNELS.store(ptr::NonNull::from(fastest).as_ptr(), Ordering::Relaxed);
+        fastest
+    } else {
+        // SAFETY: only ever set to a reference to one of the static kernel tables
+        unsafe { &*kernels }
+    }
+}
+
+// kernels() no-std: implementation selection by config
+
+#[cfg(all(feature = "armv7_neon", target_feature = "neon"))]
+#[inline]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    &neon::KERNELS
+}
+
+#[cfg(any(
+    not(feature = "armv7_neon"),
+    all(not(feature = "std"), not(target_feature = "neon"))
+))]
+#[inline]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    &super::fallback::KERNELS
+}
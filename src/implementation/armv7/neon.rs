@@ -1,8 +1,9 @@
 //! Contains the aarch64 UTF-8 validation implementation.
 
 use core::arch::arm::{
-    uint8x16_t, uint8x8x2_t, vandq_u8, vcombine_u8, vdupq_n_u8, veorq_u8, vextq_u8, vget_high_u8,
-    vget_lane_u8, vget_low_u8, vld1q_u8, vmovq_n_u8, vorrq_u8, vpmax_u8, vqsubq_u8, vshrq_n_u8,
+    uint8x16_t, uint8x8_t, uint8x8x2_t, vandq_u8, vceqq_u8, vcombine_u8, vdupq_n_u8, veorq_u8,
+    vextq_u8, vget_high_u8, vget_lane_u8, vget_low_u8, vld1q_u8, vmovq_n_u8, vorrq_u8, vpadd_u8,
+    vpmax_u8, vqsubq_u8, vreinterpretq_s8_u8, vreinterpretq_u8_s8, vshrq_n_s8, vshrq_n_u8,
     vtbl2_u8,
 };
 
@@ -29,6 +30,15 @@ unsafe fn vmaxvq_u8(v: uint8x16_t) -> u8 {
     vget_lane_u8(max, 0)
 }
 
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn vaddv_u8(v: uint8x8_t) -> u8 {
+    let sum = vpadd_u8(v, v);
+    let sum = vpadd_u8(sum, sum);
+    let sum = vpadd_u8(sum, sum);
+    vget_lane_u8(sum, 0)
+}
+
 type SimdU8Value = crate::implementation::helpers::SimdU8Value<uint8x16_t>;
 
 impl SimdU8Value {
@@ -120,6 +130,12 @@ impl SimdU8Value {
         Self(dst.assume_init())
     }
 
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn store_to(self, ptr: *mut u8) {
+        ptr.cast::<uint8x16_t>().write_unaligned(self.0);
+    }
+
     #[inline]
     #[target_feature(enable = "neon")]
     #[flexpect::e(clippy::too_many_arguments)]
@@ -228,6 +244,26 @@ impl SimdU8Value {
     unsafe fn is_ascii(self) -> bool {
         vmaxvq_u8(self.0) < 0b1000_0000_u8
     }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn cmp_eq(self, b: Self) -> Self {
+        Self(vceqq_u8(self.0, b.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn bitmask(self) -> u64 {
+        // NEON has no movemask, so the high bits are broadcast, weighted by their position
+        // and summed up per 64-bit half.
+        let high_bits = vreinterpretq_u8_s8(vshrq_n_s8(vreinterpretq_s8_u8(self.0), 7));
+        let weighted = vandq_u8(
+            high_bits,
+            Self::repeat_16(1, 2, 4, 8, 16, 32, 64, 128, 1, 2, 4, 8, 16, 32, 64, 128).0,
+        );
+        u64::from(vaddv_u8(vget_low_u8(weighted)))
+            | u64::from(vaddv_u8(vget_high_u8(weighted))) << 8
+    }
 }
 
 impl From<uint8x16_t> for SimdU8Value {
@@ -0,0 +1,333 @@
+//! Contains the scalar implementations of the auxiliary kernels.
+
+use core::mem::MaybeUninit;
+
+use super::helpers::{is_continuation_byte, SIMD_CHUNK_SIZE};
+
+/// Scalar kernels, used if no SIMD implementation is available.
+#[allow(dead_code)] // unused if a SIMD implementation is selected at compile-time
+pub(crate) static KERNELS: super::Kernels = super::Kernels {
+    count_chars,
+    count_byte,
+    rfind_byte,
+    first_non_ascii,
+    validate_many,
+    validate_and_copy,
+    validate_until_byte,
+    validate_with_stats,
+    validate_with_byte_masks,
+    validate_with_block_chars,
+    count_utf16_units,
+    skip_utf16_units,
+    block_masks,
+    validate_with_byte_bitmaps,
+};
+
+#[inline]
+pub(crate) fn count_chars(input: &[u8]) -> usize {
+    let mut count = 0;
+    for &b in input {
+        count += usize::from(!is_continuation_byte(b));
+    }
+    count
+}
+
+#[inline]
+pub(crate) fn count_byte(input: &[u8], byte: u8) -> usize {
+    let mut count = 0;
+    for &b in input {
+        count += usize::from(b == byte);
+    }
+    count
+}
+
+#[inline]
+pub(crate) fn rfind_byte(input: &[u8], byte: u8) -> Option<usize> {
+    input.iter().rposition(|&b| b == byte)
+}
+
+#[inline]
+pub(crate) fn first_non_ascii(input: &[u8]) -> Option<usize> {
+    input.iter().position(|&b| b >= 0x80)
+}
+
+#[inline]
+pub(crate) fn validate_many(inputs: &[&[u8]], results: &mut [bool]) {
+    for (input, result) in inputs.iter().zip(results.iter_mut()) {
+        *result = core::str::from_utf8(input).is_ok();
+    }
+}
+
+#[inline]
+pub(crate) fn validate_and_copy(src: &[u8], dst: &mut [MaybeUninit<u8>]) -> Result<(), usize> {
+    let dst = &mut dst[..src.len()];
+    // SAFETY: `MaybeUninit<u8>` has the layout of `u8` and all bytes are initialized by the copy
+    let copy = unsafe {
+        core::ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr().cast::<u8>(), src.len());
+        core::slice::from_raw_parts(dst.as_ptr().cast::<u8>(), src.len())
+    };
+    // the copy is validated since `src` might be modified concurrently
+    core::str::from_utf8(copy)
+        .map(|_| ())
+        .map_err(|err| err.valid_up_to())
+}
+
+#[inline]
+pub(crate) fn validate_until_byte(input: &[u8], byte: u8) -> Result<usize, usize> {
+    let end = input.iter().position(|&b| b == byte).unwrap_or(input.len());
+    core::str::from_utf8(&input[..end])
+        .map(|_| end)
+        .map_err(|err| err.valid_up_to())
+}
+
+#[inline]
+pub(crate) fn validate_with_stats(
+    input: &[u8],
+    stats: &mut crate::stats::Utf8Stats,
+) -> Result<(), usize> {
+    core::str::from_utf8(input).map_err(|err| err.valid_up_to())?;
+    stats.count_scalar(input);
+    Ok(())
+}
+
+#[inline]
+pub(crate) fn validate_with_byte_masks(
+    input: &[u8],
+    byte: u8,
+    sink: &mut dyn FnMut(usize, u64),
+) -> Result<(), usize> {
+    core::str::from_utf8(input).map_err(|err| err.valid_up_to())?;
+    for (i, block) in input.chunks(SIMD_CHUNK_SIZE).enumerate() {
+        let mut mask = 0_u64;
+        for (j, &b) in block.iter().enumerate() {
+            mask |= u64::from(b == byte) << j;
+        }
+        if mask != 0 {
+            sink(i * SIMD_CHUNK_SIZE, mask);
+        }
+    }
+    Ok(())
+}
+
+#[inline]
+pub(crate) fn validate_with_block_chars(
+    input: &[u8],
+    block_chars: &mut [usize],
+) -> Result<usize, usize> {
+    core::str::from_utf8(input).map_err(|err| err.valid_up_to())?;
+    let mut chars = 0;
+    for (block, chars_before) in input.chunks(SIMD_CHUNK_SIZE).zip(block_chars.iter_mut()) {
+        *chars_before = chars;
+        chars += count_chars(block);
+    }
+    Ok(chars)
+}
+
+#[inline]
+pub(crate) fn count_utf16_units(input: &[u8]) -> usize {
+    let mut count = 0;
+    for &b in input {
+        count += usize::from(!is_continuation_byte(b)) + usize::from(b >= 0xF0);
+    }
+    count
+}
+
+#[inline]
+pub(crate) fn skip_utf16_units(input: &[u8], units: usize) -> (usize, usize) {
+    let mut count = 0;
+    let mut idx = 0;
+    for block in input.chunks_exact(SIMD_CHUNK_SIZE) {
+        let block_units = count_utf16_units(block);
+        if count + block_units > units {
+            break;
+        }
+        count += block_units;
+        idx += SIMD_CHUNK_SIZE;
+    }
+    (idx, count)
+}
+
+// the lookup tables of the SIMD implementations, indexed by nibbles
+const TOO_SHORT: u8 = 1 << 0;
+const TOO_LONG: u8 = 1 << 1;
+const OVERLONG_3: u8 = 1 << 2;
+const TOO_LARGE: u8 = 1 << 3;
+const SURROGATE: u8 = 1 << 4;
+const OVERLONG_2: u8 = 1 << 5;
+const TOO_LARGE_1000: u8 = 1 << 6;
+const OVERLONG_4: u8 = 1 << 6;
+const TWO_CONTS: u8 = 1 << 7;
+const CARRY: u8 = TOO_SHORT | TOO_LONG | TWO_CONTS;
+
+const BYTE_1_HIGH: [u8; 16] = [
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TOO_LONG,
+    TWO_CONTS,
+    TWO_CONTS,
+    TWO_CONTS,
+    TWO_CONTS,
+    TOO_SHORT | OVERLONG_2,
+    TOO_SHORT,
+    TOO_SHORT | OVERLONG_3 | SURROGATE,
+    TOO_SHORT | TOO_LARGE | TOO_LARGE_1000 | OVERLONG_4,
+];
+
+const BYTE_1_LOW: [u8; 16] = [
+    CARRY | OVERLONG_3 | OVERLONG_2 | OVERLONG_4,
+    CARRY | OVERLONG_2,
+    CARRY,
+    CARRY,
+    CARRY | TOO_LARGE,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000 | SURROGATE,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+    CARRY | TOO_LARGE | TOO_LARGE_1000,
+];
+
+const BYTE_2_HIGH: [u8; 16] = [
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | OVERLONG_3 | TOO_LARGE_1000 | OVERLONG_4,
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | OVERLONG_3 | TOO_LARGE,
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | SURROGATE | TOO_LARGE,
+    TOO_LONG | OVERLONG_2 | TWO_CONTS | SURROGATE | TOO_LARGE,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+    TOO_SHORT,
+];
+
+/// Scalar version of the SIMD block masks, setting the error bits at the same bytes.
+#[inline]
+pub(crate) fn block_masks(input: &[u8], prev: [u8; 3], masks: &mut [crate::bitmap::BlockMasks]) {
+    let [mut prev3, mut prev2, mut prev1] = prev;
+    for (block, block_masks) in input.chunks(SIMD_CHUNK_SIZE).zip(masks.iter_mut()) {
+        block_masks.char_starts = 0;
+        block_masks.errors = 0;
+        for (i, &b) in block.iter().enumerate() {
+            let special_cases = BYTE_1_HIGH[usize::from(prev1 >> 4)]
+                & BYTE_1_LOW[usize::from(prev1 & 0x0F)]
+                & BYTE_2_HIGH[usize::from(b >> 4)];
+            let must23 = if prev2 >= 0xE0 || prev3 >= 0xF0 {
+                0x80
+            } else {
+                0
+            };
+            block_masks.char_starts |= u64::from(!is_continuation_byte(b)) << i;
+            block_masks.errors |= u64::from(special_cases ^ must23 != 0) << i;
+            prev3 = prev2;
+            prev2 = prev1;
+            prev1 = b;
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn validate_with_byte_bitmaps(
+    input: &[u8],
+    bytes: &[u8],
+    bitmaps: &mut [u64],
+) -> Result<(), usize> {
+    core::str::from_utf8(input).map_err(|err| err.valid_up_to())?;
+    let mut block_bitmaps = bitmaps.iter_mut();
+    for block in input.chunks(SIMD_CHUNK_SIZE) {
+        for &byte in bytes {
+            if let Some(bitmap) = block_bitmaps.next() {
+                *bitmap = block
+                    .iter()
+                    .rev()
+                    .fold(0, |mask, &b| mask << 1 | u64::from(b == byte));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Low-level implementation of the [`basic::imp::Utf8Validator`] trait which works on all CPUs.
+///
+/// Incomplete chars are carried over between updates and the rest of each update is validated
+/// at once. States of all implementations can be restored into it, so validation can continue
+/// on CPUs without the SIMD extensions of the implementation which took the snapshot.
+///
+/// [`basic::imp::Utf8Validator`]: crate::basic::imp::Utf8Validator
+#[cfg(feature = "public_imp")]
+#[derive(Clone)]
+pub struct Utf8ValidatorImp {
+    stream: super::stream::Utf8Stream,
+    error: bool,
+}
+
+#[cfg(feature = "public_imp")]
+impl crate::basic::imp::Utf8Validator for Utf8ValidatorImp {
+    #[inline]
+    unsafe fn new() -> Self {
+        Self {
+            stream: super::stream::Utf8Stream::new(),
+            error: false,
+        }
+    }
+
+    #[inline]
+    unsafe fn update(&mut self, input: &[u8]) {
+        if !self.error {
+            self.error = self.stream.update(input).is_err();
+        }
+    }
+
+    #[inline]
+    unsafe fn finalize(self) -> Result<(), crate::basic::Utf8Error> {
+        if self.error || self.stream.finish().is_err() {
+            Err(crate::basic::Utf8Error {})
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "public_imp")]
+impl crate::basic::imp::Utf8ValidatorSnapshot for Utf8ValidatorImp {
+    #[inline]
+    unsafe fn snapshot(&self) -> crate::basic::imp::Utf8ValidatorState {
+        // the incomplete char ends the last block, the bytes before it are treated as ASCII
+        let carried = self.stream.carried();
+        let mut state = crate::basic::imp::Utf8ValidatorState {
+            error: self.error,
+            prev: [0; 3],
+            pending: [0; 64],
+            pending_len: 0,
+        };
+        state.prev[3 - carried.len()..].copy_from_slice(carried);
+        state
+    }
+
+    #[inline]
+    unsafe fn restore(state: &crate::basic::imp::Utf8ValidatorState) -> Self {
+        use crate::basic::imp::Utf8Validator;
+
+        let mut validator = Self::new();
+        validator.error = state.error;
+        // an incomplete char at the end of the last block is completed by the pending bytes
+        validator.update(&state.prev[super::stream::incomplete_char_start(&state.prev)..]);
+        validator.update(&state.pending[..state.pending_len]);
+        validator
+    }
+}
@@ -27,6 +27,12 @@ pub(crate) unsafe fn validate_utf8_at_offset(
     }
 }
 
+/// Checks if `byte` is a UTF-8 continuation byte (`0b10xx_xxxx`).
+#[inline]
+pub(crate) const fn is_continuation_byte(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
 /// Necessary tor 1.38 compatibility
 #[inline]
 unsafe fn unwrap_err_unchecked<O, E>(r: Result<O, E>) -> E {
@@ -53,7 +59,9 @@ pub(crate) fn get_compat_error(input: &[u8], failing_block_pos: usize) -> Utf8Er
         //
         // SAFETY: safe because failing_block_pos is in bounds.
         (1..=3)
-            .find(|i| *(unsafe { input.get_unchecked(failing_block_pos - i) }) >> 6 != 0b10)
+            .find(|i| {
+                !is_continuation_byte(*(unsafe { input.get_unchecked(failing_block_pos - i) }))
+            })
             .map_or(failing_block_pos, |i| failing_block_pos - i)
     };
     // SAFETY: safe because the SIMD UTF-8 validation found an error and offset is in bounds.
@@ -114,7 +122,26 @@ pub(crate) unsafe fn memcpy_unaligned_nonoverlapping_inline_opt_lt_64(
 
 pub(crate) const SIMD_CHUNK_SIZE: usize = 64;
 
+/// Mask selecting the first `len` bytes of a 64-byte block, `len` must be in `1..64`.
+#[inline]
+#[allow(dead_code)] // only used if there is a SIMD implementation
+pub(crate) const fn partial_block_mask(len: usize) -> u64 {
+    !0_u64 >> (SIMD_CHUNK_SIZE - len)
+}
+
+/// Number of 64-byte blocks of an input of length `len`, including a partial last block.
+#[inline]
+pub(crate) fn block_count(len: usize) -> usize {
+    let full = len / SIMD_CHUNK_SIZE;
+    if full * SIMD_CHUNK_SIZE < len {
+        full + 1
+    } else {
+        full
+    }
+}
+
 #[repr(C, align(32))]
+#[derive(Clone, Copy)]
 #[allow(dead_code)] // only used if there is a SIMD implementation
 pub(crate) struct Utf8CheckAlgorithm<T> {
     pub(crate) prev: T,
@@ -1,11 +1,17 @@
 //! Contains UTF-8 validation implementations.
 
+use core::mem::MaybeUninit;
+
 #[macro_use]
 #[allow(unused_macros)] // only used if there is a SIMD implementation
 mod algorithm;
 
 pub(crate) mod helpers;
 
+pub(crate) mod fallback;
+
+pub(crate) mod stream;
+
 // UTF-8 validation function types
 
 // x86 implementation
@@ -92,6 +98,291 @@ pub(super) use validate_utf8_basic_fallback as validate_utf8_basic;
 )))]
 pub(super) use validate_utf8_compat_fallback as validate_utf8_compat;
 
+// auxiliary SIMD kernels
+
+/// Table of the auxiliary kernels of one implementation.
+///
+/// Every SIMD implementation provides a `KERNELS` static of this type, [`fallback::KERNELS`]
+/// holds the scalar versions. The kernels expect at least [`helpers::SIMD_CHUNK_SIZE`] bytes
+/// of input for best performance but work with any length.
+#[flexpect::e(clippy::type_complexity)] // plain kernel signatures
+pub(crate) struct Kernels {
+    pub(crate) count_chars: unsafe fn(input: &[u8]) -> usize,
+    pub(crate) count_byte: unsafe fn(input: &[u8], byte: u8) -> usize,
+    pub(crate) rfind_byte: unsafe fn(input: &[u8], byte: u8) -> Option<usize>,
+    pub(crate) first_non_ascii: unsafe fn(input: &[u8]) -> Option<usize>,
+    pub(crate) validate_many: unsafe fn(inputs: &[&[u8]], results: &mut [bool]),
+    pub(crate) validate_and_copy:
+        unsafe fn(src: &[u8], dst: &mut [MaybeUninit<u8>]) -> Result<(), usize>,
+    pub(crate) validate_until_byte: unsafe fn(input: &[u8], byte: u8) -> Result<usize, usize>,
+    pub(crate) validate_with_stats:
+        unsafe fn(input: &[u8], stats: &mut crate::stats::Utf8Stats) -> Result<(), usize>,
+    #[allow(dead_code)] // only used with std
+    pub(crate) validate_with_byte_masks:
+        unsafe fn(input: &[u8], byte: u8, sink: &mut dyn FnMut(usize, u64)) -> Result<(), usize>,
+    #[allow(dead_code)] // only used with std
+    pub(crate) validate_with_block_chars:
+        unsafe fn(input: &[u8], block_chars: &mut [usize]) -> Result<usize, usize>,
+    pub(crate) count_utf16_units: unsafe fn(input: &[u8]) -> usize,
+    pub(crate) skip_utf16_units: unsafe fn(input: &[u8], units: usize) -> (usize, usize),
+    pub(crate) block_masks:
+        unsafe fn(input: &[u8], prev: [u8; 3], masks: &mut [crate::bitmap::BlockMasks]),
+    pub(crate) validate_with_byte_bitmaps:
+        unsafe fn(input: &[u8], bytes: &[u8], bitmaps: &mut [u64]) -> Result<(), usize>,
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use x86::kernels;
+
+#[cfg(all(target_arch = "arm", target_feature = "v7", target_endian = "little"))]
+use armv7::kernels;
+
+#[cfg(target_arch = "aarch64")]
+use aarch64::kernels;
+
+#[cfg(target_arch = "wasm32")]
+use wasm32::kernels;
+
+#[cfg(not(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "aarch64",
+    all(target_arch = "arm", target_feature = "v7", target_endian = "little"),
+    target_arch = "wasm32"
+)))]
+#[inline]
+fn kernels() -> &'static Kernels {
+    &fallback::KERNELS
+}
+
+/// Counts the chars (UTF-8 code points) of valid UTF-8 `input`.
+#[inline]
+pub(crate) fn count_chars(input: &[u8]) -> usize {
+    if input.len() < helpers::SIMD_CHUNK_SIZE {
+        return fallback::count_chars(input);
+    }
+    // SAFETY: kernels() only returns implementations supported by the CPU
+    unsafe { (kernels().count_chars)(input) }
+}
+
+/// Counts the occurrences of `byte` in `input`.
+#[inline]
+pub(crate) fn count_byte(input: &[u8], byte: u8) -> usize {
+    if input.len() < helpers::SIMD_CHUNK_SIZE {
+        return fallback::count_byte(input, byte);
+    }
+    // SAFETY: kernels() only returns implementations supported by the CPU
+    unsafe { (kernels().count_byte)(input, byte) }
+}
+
+/// Returns the position of the last occurrence of `byte` in `input`.
+#[inline]
+pub(crate) fn rfind_byte(input: &[u8], byte: u8) -> Option<usize> {
+    if input.len() < helpers::SIMD_CHUNK_SIZE {
+        return fallback::rfind_byte(input, byte);
+    }
+    // SAFETY: kernels() only returns implementations supported by the CPU
+    unsafe { (kernels().rfind_byte)(input, byte) }
+}
+
+/// Returns the position of the first byte of `input` which is not ASCII.
+#[inline]
+pub(crate) fn first_non_ascii(input: &[u8]) -> Option<usize> {
+    if input.len() < helpers::SIMD_CHUNK_SIZE {
+        return fallback::first_non_ascii(input);
+    }
+    // SAFETY: kernels() only returns implementations supported by the CPU
+    unsafe { (kernels().first_non_ascii)(input) }
+}
+
+/// Validates each of `inputs` and stores the results in the corresponding elements of
+/// `results`, which must have the same length.
+#[inline]
+pub(crate) fn validate_many(inputs: &[&[u8]], results: &mut [bool]) {
+    assert_eq!(
+        inputs.len(),
+        results.len(),
+        "inputs and results must have the same length"
+    );
+    // SAFETY: kernels() only returns implementations supported by the CPU, lengths are checked
+    unsafe { (kernels().validate_many)(inputs, results) }
+}
+
+/// Copies `src` to the start of `dst` and validates the copied bytes in a single pass.
+///
+/// Returns `Err(pos)` if the copy is not valid UTF-8. The error can be determined with
+/// [`helpers::get_compat_error()`] from `pos` and the first `pos + SIMD_CHUNK_SIZE` copied
+/// bytes.
+#[inline]
+pub(crate) fn validate_and_copy(src: &[u8], dst: &mut [MaybeUninit<u8>]) -> Result<(), usize> {
+    assert!(
+        dst.len() >= src.len(),
+        "destination must not be shorter than the source"
+    );
+    if src.len() < helpers::SIMD_CHUNK_SIZE {
+        return fallback::validate_and_copy(src, dst);
+    }
+    // SAFETY: kernels() only returns implementations supported by the CPU, lengths are checked
+    unsafe { (kernels().validate_and_copy)(src, dst) }
+}
+
+/// Finds the first occurrence of `byte` in `input` and validates the bytes before it in a single
+/// pass.
+///
+/// Returns the position of `byte` or the input length if it does not occur.
+#[inline]
+pub(crate) fn validate_until_byte(
+    input: &[u8],
+    byte: u8,
+) -> Result<usize, crate::compat::Utf8Error> {
+    let res = if input.len() < helpers::SIMD_CHUNK_SIZE {
+        fallback::validate_until_byte(input, byte)
+    } else {
+        // SAFETY: kernels() only returns implementations supported by the CPU
+        unsafe { (kernels().validate_until_byte)(input, byte) }
+    };
+    res.map_err(|pos| {
+        // the error is before `byte` if it does not occur in the failing block
+        let block_end = core::cmp::min(pos + helpers::SIMD_CHUNK_SIZE, input.len());
+        let end = input[pos..block_end]
+            .iter()
+            .position(|&b| b == byte)
+            .map_or(input.len(), |i| pos + i);
+        helpers::get_compat_error(&input[..end], pos)
+    })
+}
+
+/// Validates `input` and adds its statistics to `stats` in a single pass.
+///
+/// `stats` is not modified if the input is not valid UTF-8.
+#[inline]
+pub(crate) fn validate_with_stats(
+    input: &[u8],
+    stats: &mut crate::stats::Utf8Stats,
+) -> Result<(), crate::compat::Utf8Error> {
+    let mut counts = crate::stats::Utf8Stats::default();
+    let res = if input.len() < helpers::SIMD_CHUNK_SIZE {
+        fallback::validate_with_stats(input, &mut counts)
+    } else {
+        // SAFETY: kernels() only returns implementations supported by the CPU
+        unsafe { (kernels().validate_with_stats)(input, &mut counts) }
+    };
+    res.map_err(|pos| helpers::get_compat_error(input, pos))?;
+    counts.len = input.len();
+    stats.add(&counts);
+    Ok(())
+}
+
+/// Validates `input` and passes the positions of `byte` to `sink` in a single pass.
+///
+/// `sink` is called in input order with the start of each [`helpers::SIMD_CHUNK_SIZE`] block
+/// containing `byte` and the bit mask of its occurrences in the block. The calls must be
+/// discarded if the input is not valid UTF-8.
+#[inline]
+#[allow(dead_code)] // only used with std
+pub(crate) fn validate_with_byte_masks(
+    input: &[u8],
+    byte: u8,
+    sink: &mut dyn FnMut(usize, u64),
+) -> Result<(), crate::compat::Utf8Error> {
+    let res = if input.len() < helpers::SIMD_CHUNK_SIZE {
+        fallback::validate_with_byte_masks(input, byte, sink)
+    } else {
+        // SAFETY: kernels() only returns implementations supported by the CPU
+        unsafe { (kernels().validate_with_byte_masks)(input, byte, sink) }
+    };
+    res.map_err(|pos| helpers::get_compat_error(input, pos))
+}
+
+/// Validates `input` and stores the number of chars before each [`helpers::SIMD_CHUNK_SIZE`]
+/// block in `block_chars` in a single pass.
+///
+/// Returns the number of chars of the input. `block_chars` must have an element for each block,
+/// including the partial last one.
+#[inline]
+#[allow(dead_code)] // only used with std
+pub(crate) fn validate_with_block_chars(
+    input: &[u8],
+    block_chars: &mut [usize],
+) -> Result<usize, crate::compat::Utf8Error> {
+    assert!(
+        block_chars.len() >= helpers::block_count(input.len()),
+        "block_chars must have an element for each block"
+    );
+    let res = if input.len() < helpers::SIMD_CHUNK_SIZE {
+        fallback::validate_with_block_chars(input, block_chars)
+    } else {
+        // SAFETY: kernels() only returns implementations supported by the CPU, lengths are checked
+        unsafe { (kernels().validate_with_block_chars)(input, block_chars) }
+    };
+    res.map_err(|pos| helpers::get_compat_error(input, pos))
+}
+
+/// Counts the UTF-16 code units needed to encode valid UTF-8 `input`.
+#[inline]
+pub(crate) fn count_utf16_units(input: &[u8]) -> usize {
+    if input.len() < helpers::SIMD_CHUNK_SIZE {
+        return fallback::count_utf16_units(input);
+    }
+    // SAFETY: kernels() only returns implementations supported by the CPU
+    unsafe { (kernels().count_utf16_units)(input) }
+}
+
+/// Skips complete [`helpers::SIMD_CHUNK_SIZE`] blocks of valid UTF-8 `input` as long as the chars
+/// starting in them need at most `units` UTF-16 code units in total.
+///
+/// Returns the start of the first block which was not skipped and the number of code units of
+/// the chars starting before it.
+#[inline]
+pub(crate) fn skip_utf16_units(input: &[u8], units: usize) -> (usize, usize) {
+    if input.len() < helpers::SIMD_CHUNK_SIZE {
+        return (0, 0);
+    }
+    // SAFETY: kernels() only returns implementations supported by the CPU
+    unsafe { (kernels().skip_utf16_units)(input, units) }
+}
+
+/// Computes the char start and error masks of each [`helpers::SIMD_CHUNK_SIZE`] block of `input`.
+///
+/// `prev` holds the last three bytes before the input. An incomplete char at the end of the
+/// input is not reported.
+#[inline]
+pub(crate) fn block_masks(input: &[u8], prev: [u8; 3], masks: &mut [crate::bitmap::BlockMasks]) {
+    assert!(
+        masks.len() >= helpers::block_count(input.len()),
+        "masks must have an element for each block"
+    );
+    if input.len() < helpers::SIMD_CHUNK_SIZE {
+        return fallback::block_masks(input, prev, masks);
+    }
+    // SAFETY: kernels() only returns implementations supported by the CPU, lengths are checked
+    unsafe { (kernels().block_masks)(input, prev, masks) }
+}
+
+/// Validates `input` and stores the masks of the occurrences of each of `bytes` in each
+/// [`helpers::SIMD_CHUNK_SIZE`] block in a single pass.
+///
+/// The masks of block `i` are stored at `bitmaps[i * bytes.len()..(i + 1) * bytes.len()]`.
+/// `bytes` must not be empty.
+#[inline]
+pub(crate) fn validate_with_byte_bitmaps(
+    input: &[u8],
+    bytes: &[u8],
+    bitmaps: &mut [u64],
+) -> Result<(), crate::compat::Utf8Error> {
+    assert!(
+        bitmaps.len() / bytes.len() >= helpers::block_count(input.len()),
+        "bitmaps must have an element for each byte in each block"
+    );
+    let res = if input.len() < helpers::SIMD_CHUNK_SIZE {
+        fallback::validate_with_byte_bitmaps(input, bytes, bitmaps)
+    } else {
+        // SAFETY: kernels() only returns implementations supported by the CPU, lengths are checked
+        unsafe { (kernels().validate_with_byte_bitmaps)(input, bytes, bitmaps) }
+    };
+    res.map_err(|pos| helpers::get_compat_error(input, pos))
+}
+
 // portable SIMD implementation
 
 #[cfg(feature = "portable_public_imp")]
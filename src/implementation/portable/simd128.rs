@@ -79,6 +79,11 @@ impl SimdU8Value {
         Self::from(ptr.cast::<u8x16>().read_unaligned())
     }
 
+    #[inline]
+    unsafe fn store_to(self, ptr: *mut u8) {
+        ptr.cast::<u8x16>().write_unaligned(self.0);
+    }
+
     #[inline]
     fn lookup_16(
         self,
@@ -193,6 +198,20 @@ impl SimdU8Value {
             (self.0 & u8x16::splat(0b1000_0000)) == u8x16::splat(0)
         }
     }
+
+    #[inline]
+    fn cmp_eq(self, b: Self) -> Self {
+        Self::from(
+            self.0
+                .simd_eq(b.0)
+                .select(u8x16::splat(0xFF), u8x16::splat(0)),
+        )
+    }
+
+    #[inline]
+    fn bitmask(self) -> u64 {
+        self.0.simd_ge(u8x16::splat(0b1000_0000)).to_bitmask()
+    }
 }
 
 impl From<u8x16> for SimdU8Value {
@@ -81,6 +81,11 @@ impl SimdU8Value {
         Self::from(ptr.cast::<u8x32>().read_unaligned())
     }
 
+    #[inline]
+    unsafe fn store_to(self, ptr: *mut u8) {
+        ptr.cast::<u8x32>().write_unaligned(self.0);
+    }
+
     #[inline]
     fn lookup_16(
         self,
@@ -198,6 +203,20 @@ impl SimdU8Value {
         let significan_bits = self.0 & u8x32::from_array([0b1000_0000; 32]);
         significan_bits == u8x32::from_array([0; 32])
     }
+
+    #[inline]
+    fn cmp_eq(self, b: Self) -> Self {
+        Self::from(
+            self.0
+                .simd_eq(b.0)
+                .select(u8x32::splat(0xFF), u8x32::splat(0)),
+        )
+    }
+
+    #[inline]
+    fn bitmask(self) -> u64 {
+        self.0.simd_ge(u8x32::splat(0b1000_0000)).to_bitmask()
+    }
 }
 
 impl From<u8x32> for SimdU8Value {
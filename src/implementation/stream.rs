@@ -0,0 +1,205 @@
+//! Safe streaming validation on top of the one-shot validators.
+
+use crate::compat::{from_utf8, Utf8Error};
+use crate::implementation::helpers::is_continuation_byte;
+
+/// Streaming validator which carries incomplete chars between updates.
+///
+/// Each update is validated with the selected one-shot implementation. An incomplete char at
+/// the end of an update is kept and completed with the first bytes of the next update.
+/// Errors are reported with offsets relative to the start of the stream.
+#[derive(Clone, Default)]
+pub(crate) struct Utf8Stream {
+    carry: [u8; 4],
+    carry_len: usize,
+    offset: usize,
+}
+
+/// Length of the UTF-8 sequence starting with the valid lead byte `lead`.
+#[inline]
+fn sequence_len(lead: u8) -> usize {
+    if lead < 0xE0 {
+        2
+    } else if lead < 0xF0 {
+        3
+    } else {
+        4
+    }
+}
+
+/// Additional work done in the same pass as the validation of a [`Utf8Stream`].
+pub(crate) trait FusedSink {
+    /// Processes a char which was completed from an incomplete char carried over from the
+    /// previous update. `offset` is its position in the stream.
+    fn complete_char(&mut self, ch: &[u8], offset: usize);
+
+    /// Validates and processes `input` starting at `offset` in the stream, which starts at a
+    /// char boundary. Returns `false` if it is not valid UTF-8.
+    fn validate(&mut self, input: &[u8], offset: usize) -> bool;
+}
+
+/// Returns the start of an incomplete char at the end of `input` or the input length.
+#[inline]
+pub(crate) fn incomplete_char_start(input: &[u8]) -> usize {
+    let len = input.len();
+    for i in 1..=core::cmp::min(3, len) {
+        let b = input[len - i];
+        if !is_continuation_byte(b) {
+            if b >= 0xC0 && sequence_len(b) > i {
+                return len - i;
+            }
+            break;
+        }
+    }
+    len
+}
+
+impl Utf8Stream {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if no incomplete char is carried over to the next update.
+    #[inline]
+    #[allow(dead_code)] // only used with std
+    pub(crate) fn is_at_char_boundary(&self) -> bool {
+        self.carry_len == 0
+    }
+
+    /// Returns the number of bytes of the incomplete char carried over to the next update.
+    #[inline]
+    #[allow(dead_code)] // only used with std
+    pub(crate) fn pending_len(&self) -> usize {
+        self.carry_len
+    }
+
+    /// Returns the bytes of the incomplete char carried over to the next update.
+    #[cfg(feature = "public_imp")]
+    #[inline]
+    pub(crate) fn carried(&self) -> &[u8] {
+        &self.carry[..self.carry_len]
+    }
+
+    /// Returns the number of bytes missing to complete the carried incomplete char.
+    #[inline]
+    #[allow(dead_code)] // only used with std
+    pub(crate) fn missing_len(&self) -> usize {
+        if self.carry_len == 0 {
+            0
+        } else {
+            sequence_len(self.carry[0]) - self.carry_len
+        }
+    }
+
+    /// Advances the stream over `len` bytes which are known to be valid UTF-8 by themselves.
+    ///
+    /// Must only be called at a char boundary.
+    #[inline]
+    #[allow(dead_code)] // only used with std
+    pub(crate) fn skip_valid(&mut self, len: usize) {
+        debug_assert!(self.is_at_char_boundary());
+        self.offset += len;
+    }
+
+    /// Validates the next part of the stream.
+    ///
+    /// The state is unspecified after an error.
+    #[inline]
+    pub(crate) fn update(&mut self, input: &[u8]) -> Result<(), Utf8Error> {
+        let rest = self.complete_carry(input)?;
+        let rest_start = self.offset;
+        self.offset += rest.len();
+        match from_utf8(rest) {
+            Ok(_) => Ok(()),
+            Err(err) if err.error_len().is_some() => Err(err.offset_by(rest_start)),
+            Err(err) => {
+                self.set_carry(&rest[err.valid_up_to()..]);
+                Ok(())
+            }
+        }
+    }
+
+    /// Like [`Self::update()`], but validates the complete chars with `sink`, which does
+    /// additional work in the same pass.
+    #[inline]
+    pub(crate) fn update_fused<S: FusedSink + ?Sized>(
+        &mut self,
+        input: &[u8],
+        sink: &mut S,
+    ) -> Result<(), Utf8Error> {
+        let carry_len = self.carry_len;
+        let rest = self.complete_carry(input)?;
+        if carry_len != 0 && self.carry_len == 0 {
+            let char_len = sequence_len(self.carry[0]);
+            sink.complete_char(&self.carry[..char_len], self.offset - char_len);
+        }
+        let rest_start = self.offset;
+        self.offset += rest.len();
+        // an incomplete char at the end is carried over without being passed to the sink
+        let end = incomplete_char_start(rest);
+        let checked = if sink.validate(&rest[..end], rest_start) {
+            &rest[end..]
+        } else {
+            // the error offsets depend on the bytes after `end`
+            rest
+        };
+        let checked_start = rest_start + rest.len() - checked.len();
+        match from_utf8(checked) {
+            Ok(_) => Ok(()),
+            Err(err) if err.error_len().is_some() => Err(err.offset_by(checked_start)),
+            Err(err) => {
+                self.set_carry(&checked[err.valid_up_to()..]);
+                Ok(())
+            }
+        }
+    }
+
+    /// Completes the carried incomplete char with the first bytes of `input`.
+    ///
+    /// Returns the rest of the input, which is empty if the char is still incomplete.
+    #[inline]
+    fn complete_carry<'a>(&mut self, input: &'a [u8]) -> Result<&'a [u8], Utf8Error> {
+        if self.carry_len == 0 {
+            return Ok(input);
+        }
+        let carry_start = self.offset - self.carry_len;
+        let missing = sequence_len(self.carry[0]) - self.carry_len;
+        let take = core::cmp::min(missing, input.len());
+        self.carry[self.carry_len..self.carry_len + take].copy_from_slice(&input[..take]);
+        self.carry_len += take;
+        self.offset += take;
+        match from_utf8(&self.carry[..self.carry_len]) {
+            Ok(_) => {
+                self.carry_len = 0;
+                Ok(&input[take..])
+            }
+            Err(err) if err.error_len().is_some() => Err(err.offset_by(carry_start)),
+            // still incomplete, input is exhausted
+            Err(_) => Ok(&[]),
+        }
+    }
+
+    /// Carries the incomplete char `tail` over to the next update.
+    #[inline]
+    fn set_carry(&mut self, tail: &[u8]) {
+        self.carry[..tail.len()].copy_from_slice(tail);
+        self.carry_len = tail.len();
+    }
+
+    /// Finishes the stream.
+    ///
+    /// # Errors
+    /// Fails if the stream ends with an incomplete char.
+    #[inline]
+    pub(crate) fn finish(&self) -> Result<(), Utf8Error> {
+        if self.carry_len == 0 {
+            Ok(())
+        } else {
+            Err(Utf8Error {
+                valid_up_to: self.offset - self.carry_len,
+                error_len: None,
+            })
+        }
+    }
+}
@@ -38,3 +38,15 @@ unsafe fn validate_utf8_compat_simd128(input: &[u8]) -> Result<(), crate::compat
 
 #[cfg(not(target_feature = "simd128"))]
 pub(crate) use super::validate_utf8_compat_fallback as validate_utf8_compat;
+
+#[inline]
+#[cfg(target_feature = "simd128")]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    &simd128::KERNELS
+}
+
+#[inline]
+#[cfg(not(target_feature = "simd128"))]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    &super::fallback::KERNELS
+}
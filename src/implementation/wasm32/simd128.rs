@@ -1,8 +1,8 @@
 //! Contains the wasm32 UTF-8 validation implementation.
 
 use core::arch::wasm32::{
-    u8x16, u8x16_all_true, u8x16_lt, u8x16_shr, u8x16_shuffle, u8x16_splat, u8x16_sub_sat,
-    u8x16_swizzle, v128, v128_and, v128_any_true, v128_or, v128_xor,
+    u8x16, u8x16_all_true, u8x16_bitmask, u8x16_eq, u8x16_lt, u8x16_shr, u8x16_shuffle,
+    u8x16_splat, u8x16_sub_sat, u8x16_swizzle, v128, v128_and, v128_any_true, v128_or, v128_xor,
 };
 
 use crate::implementation::helpers::Utf8CheckAlgorithm;
@@ -90,6 +90,11 @@ impl SimdU8Value {
         Self::from(ptr.cast::<v128>().read_unaligned())
     }
 
+    #[inline]
+    unsafe fn store_to(self, ptr: *mut u8) {
+        ptr.cast::<v128>().write_unaligned(self.0);
+    }
+
     #[inline]
     #[flexpect::e(clippy::too_many_arguments)]
     unsafe fn lookup_16(
@@ -237,6 +242,16 @@ impl SimdU8Value {
         // For x86 shifts should also be avoided.
         u8x16_all_true(u8x16_lt(self.0, u8x16_splat(0b1000_0000_u8)))
     }
+
+    #[inline]
+    unsafe fn cmp_eq(self, b: Self) -> Self {
+        Self::from(u8x16_eq(self.0, b.0))
+    }
+
+    #[inline]
+    unsafe fn bitmask(self) -> u64 {
+        u64::from(u8x16_bitmask(self.0))
+    }
 }
 
 impl From<v128> for SimdU8Value {
@@ -2,17 +2,19 @@
 
 #[cfg(target_arch = "x86")]
 use core::arch::x86::{
-    __m256i, _mm256_alignr_epi8, _mm256_and_si256, _mm256_loadu_si256, _mm256_movemask_epi8,
-    _mm256_or_si256, _mm256_permute2x128_si256, _mm256_set1_epi8, _mm256_setr_epi8,
-    _mm256_setzero_si256, _mm256_shuffle_epi8, _mm256_srli_epi16, _mm256_subs_epu8,
-    _mm256_testz_si256, _mm256_xor_si256, _mm_prefetch, _MM_HINT_T0,
+    __m256i, _mm256_alignr_epi8, _mm256_and_si256, _mm256_cmpeq_epi8, _mm256_loadu_si256,
+    _mm256_movemask_epi8, _mm256_or_si256, _mm256_permute2x128_si256, _mm256_set1_epi8,
+    _mm256_setr_epi8, _mm256_setzero_si256, _mm256_shuffle_epi8, _mm256_srli_epi16,
+    _mm256_storeu_si256, _mm256_subs_epu8, _mm256_testz_si256, _mm256_xor_si256, _mm_prefetch,
+    _MM_HINT_T0,
 };
 #[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::{
-    __m256i, _mm256_alignr_epi8, _mm256_and_si256, _mm256_loadu_si256, _mm256_movemask_epi8,
-    _mm256_or_si256, _mm256_permute2x128_si256, _mm256_set1_epi8, _mm256_setr_epi8,
-    _mm256_setzero_si256, _mm256_shuffle_epi8, _mm256_srli_epi16, _mm256_subs_epu8,
-    _mm256_testz_si256, _mm256_xor_si256, _mm_prefetch, _MM_HINT_T0,
+    __m256i, _mm256_alignr_epi8, _mm256_and_si256, _mm256_cmpeq_epi8, _mm256_loadu_si256,
+    _mm256_movemask_epi8, _mm256_or_si256, _mm256_permute2x128_si256, _mm256_set1_epi8,
+    _mm256_setr_epi8, _mm256_setzero_si256, _mm256_shuffle_epi8, _mm256_srli_epi16,
+    _mm256_storeu_si256, _mm256_subs_epu8, _mm256_testz_si256, _mm256_xor_si256, _mm_prefetch,
+    _MM_HINT_T0,
 };
 
 use crate::implementation::helpers::Utf8CheckAlgorithm;
@@ -102,6 +104,13 @@ impl SimdU8Value {
         Self::from(_mm256_loadu_si256(ptr.cast::<__m256i>()))
     }
 
+    #[flexpect::e(clippy::cast_ptr_alignment)]
+    #[target_feature(enable = "avx2")]
+    #[inline]
+    unsafe fn store_to(self, ptr: *mut u8) {
+        _mm256_storeu_si256(ptr.cast::<__m256i>(), self.0);
+    }
+
     #[flexpect::e(clippy::too_many_arguments)]
     #[target_feature(enable = "avx2")]
     #[inline]
@@ -221,6 +230,19 @@ impl SimdU8Value {
     unsafe fn is_ascii(self) -> bool {
         _mm256_movemask_epi8(self.0) == 0
     }
+
+    #[target_feature(enable = "avx2")]
+    #[inline]
+    unsafe fn cmp_eq(self, b: Self) -> Self {
+        Self::from(_mm256_cmpeq_epi8(self.0, b.0))
+    }
+
+    #[flexpect::e(clippy::cast_sign_loss)]
+    #[target_feature(enable = "avx2")]
+    #[inline]
+    unsafe fn bitmask(self) -> u64 {
+        u64::from(_mm256_movemask_epi8(self.0) as u32)
+    }
 }
 
 impl From<__m256i> for SimdU8Value {
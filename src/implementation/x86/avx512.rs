@@ -2,20 +2,20 @@
 
 #[cfg(target_arch = "x86")]
 use core::arch::x86::{
-    __m512i, _mm512_alignr_epi8, _mm512_and_si512, _mm512_loadu_si512, _mm512_maskz_loadu_epi8,
-    _mm512_movepi8_mask, _mm512_or_si512, _mm512_permutex2var_epi64, _mm512_set1_epi8,
-    _mm512_set_epi64, _mm512_set_epi8, _mm512_setzero_si512, _mm512_shuffle_epi8,
-    _mm512_srli_epi16, _mm512_subs_epu8, _mm512_test_epi8_mask, _mm512_xor_si512, _mm_prefetch,
-    _MM_HINT_T0,
+    __m512i, _mm512_alignr_epi8, _mm512_and_si512, _mm512_cmpeq_epi8_mask, _mm512_loadu_si512,
+    _mm512_maskz_loadu_epi8, _mm512_movepi8_mask, _mm512_movm_epi8, _mm512_or_si512,
+    _mm512_permutex2var_epi64, _mm512_set1_epi8, _mm512_set_epi64, _mm512_set_epi8,
+    _mm512_setzero_si512, _mm512_shuffle_epi8, _mm512_srli_epi16, _mm512_storeu_si512,
+    _mm512_subs_epu8, _mm512_test_epi8_mask, _mm512_xor_si512, _mm_prefetch, _MM_HINT_T0,
 };
 
 #[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::{
-    __m512i, _mm512_alignr_epi8, _mm512_and_si512, _mm512_loadu_si512, _mm512_maskz_loadu_epi8,
-    _mm512_movepi8_mask, _mm512_or_si512, _mm512_permutex2var_epi64, _mm512_set1_epi8,
-    _mm512_set_epi64, _mm512_set_epi8, _mm512_setzero_si512, _mm512_shuffle_epi8,
-    _mm512_srli_epi16, _mm512_subs_epu8, _mm512_test_epi8_mask, _mm512_xor_si512, _mm_prefetch,
-    _MM_HINT_T0,
+    __m512i, _mm512_alignr_epi8, _mm512_and_si512, _mm512_cmpeq_epi8_mask, _mm512_loadu_si512,
+    _mm512_maskz_loadu_epi8, _mm512_movepi8_mask, _mm512_movm_epi8, _mm512_or_si512,
+    _mm512_permutex2var_epi64, _mm512_set1_epi8, _mm512_set_epi64, _mm512_set_epi8,
+    _mm512_setzero_si512, _mm512_shuffle_epi8, _mm512_srli_epi16, _mm512_storeu_si512,
+    _mm512_subs_epu8, _mm512_test_epi8_mask, _mm512_xor_si512, _mm_prefetch, _MM_HINT_T0,
 };
 
 use crate::implementation::helpers::Utf8CheckAlgorithm;
@@ -116,6 +116,13 @@ impl SimdU8Value {
         Self::from(_mm512_loadu_si512(ptr.cast::<__m512i>()))
     }
 
+    #[flexpect::e(clippy::cast_ptr_alignment)]
+    #[target_feature(enable = "avx512f,avx512bw,avx512vbmi")]
+    #[inline]
+    unsafe fn store_to(self, ptr: *mut u8) {
+        _mm512_storeu_si512(ptr.cast::<__m512i>(), self.0);
+    }
+
     #[target_feature(enable = "avx512f,avx512bw,avx512vbmi")]
     #[inline]
     unsafe fn load_from_partial(ptr: *const u8, len: usize) -> Self {
@@ -244,6 +251,18 @@ impl SimdU8Value {
     unsafe fn is_ascii(self) -> bool {
         _mm512_movepi8_mask(self.0) == 0
     }
+
+    #[target_feature(enable = "avx512f,avx512bw,avx512vbmi")]
+    #[inline]
+    unsafe fn cmp_eq(self, b: Self) -> Self {
+        Self::from(_mm512_movm_epi8(_mm512_cmpeq_epi8_mask(self.0, b.0)))
+    }
+
+    #[target_feature(enable = "avx512f,avx512bw,avx512vbmi")]
+    #[inline]
+    unsafe fn bitmask(self) -> u64 {
+        _mm512_movepi8_mask(self.0)
+    }
 }
 
 impl From<__m512i> for SimdU8Value {
@@ -270,7 +289,6 @@ unsafe fn simd_prefetch(ptr: *const u8) {
 }
 
 const PREFETCH: bool = true;
-#[cfg(feature = "public_imp")]
 use crate::implementation::helpers::TempSimdChunkA64 as TempSimdChunk;
 simd_input_512_bit!(#[target_feature(enable = "avx512f,avx512bw,avx512vbmi")]);
 algorithm_simd!(#[target_feature(enable = "avx512f,avx512bw,avx512vbmi")]);
@@ -481,3 +481,135 @@ pub(crate) unsafe fn validate_utf8_compat_sse42(
     not(target_feature = "sse4.2")
 ))]
 pub(crate) use super::validate_utf8_compat_fallback as validate_utf8_compat;
+
+// kernels() std: implementation auto-selection
+
+#[cfg(all(
+    feature = "std",
+    not(all(
+        avx512_stable,
+        target_feature = "avx512f",
+        target_feature = "avx512bw",
+        target_feature = "avx512vbmi",
+        target_feature = "avx512vbmi2"
+    )),
+    not(all(not(avx512_stable), target_feature = "avx2"))
+))]
+#[inline]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    use core::ptr;
+    use std::sync::atomic::{AtomicPtr, Ordering};
+
+    #[cfg(avx512_stable)]
+    #[inline]
+    fn get_avx512_kernels() -> Option<&'static super::Kernels> {
+        // Test for avx512vbmi2 to make sure we have a newer CPU with a non-throttling AVX-512 implementation
+        if std::is_x86_feature_detected!("avx512f")
+            && std::is_x86_feature_detected!("avx512bw")
+            && std::is_x86_feature_detected!("avx512vbmi")
+            && std::is_x86_feature_detected!("avx512vbmi2")
+        {
+            return Some(&avx512::KERNELS);
+        }
+        None
+    }
+
+    #[cfg(not(avx512_stable))]
+    #[inline]
+    fn get_avx512_kernels() -> Option<&'static super::Kernels> {
+        None
+    }
+
+    #[flexpect::e(clippy::option_if_let_else)]
+    #[cold]
+    fn get_fastest_available_kernels() -> &'static super::Kernels {
+        if let Some(kernels) = get_avx512_kernels() {
+            kernels
+        } else if std::is_x86_feature_detected!("avx2") {
+            &avx2::KERNELS
+        } else if std::is_x86_feature_detected!("sse4.2") {
+            &sse42::KERNELS
+        } else {
+            &super::fallback::KERNELS
+        }
+    }
+
+    static KERNELS: AtomicPtr<super::Kernels> = AtomicPtr::new(ptr::null_mut());
+
+    let kernels = KERNELS.load(Ordering::Relaxed);
+    if kernels.is_null() {
+        let fastest = get_fastest_available_kernels();
+        KERNELS.store(ptr::NonNull::from(fastest).as_ptr(), Ordering::Relaxed);
+        fastest
+    } else {
+        // SAFETY: only ever set to a reference to one of the static kernel tables
+        unsafe { &*kernels }
+    }
+}
+
+// kernels() no-std: implementation selection by config
+
+#[cfg(all(
+    avx512_stable,
+    target_feature = "avx512f",
+    target_feature = "avx512bw",
+    target_feature = "avx512vbmi",
+    target_feature = "avx512vbmi2"
+))]
+#[inline]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    &avx512::KERNELS
+}
+
+#[cfg(any(
+    all(
+        not(feature = "std"),
+        not(all(
+            avx512_stable,
+            target_feature = "avx512f",
+            target_feature = "avx512bw",
+            target_feature = "avx512vbmi",
+            target_feature = "avx512vbmi2"
+        )),
+        target_feature = "avx2"
+    ),
+    all(target_feature = "avx2", feature = "std", not(avx512_stable))
+))]
+#[inline]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    &avx2::KERNELS
+}
+
+#[cfg(all(
+    not(feature = "std"),
+    not(all(
+        avx512_stable,
+        target_feature = "avx512f",
+        target_feature = "avx512bw",
+        target_feature = "avx512vbmi",
+        target_feature = "avx512vbmi2"
+    )),
+    not(target_feature = "avx2"),
+    target_feature = "sse4.2"
+))]
+#[inline]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    &sse42::KERNELS
+}
+
+#[cfg(all(
+    not(feature = "std"),
+    not(all(
+        avx512_stable,
+        target_feature = "avx512f",
+        target_feature = "avx512bw",
+        target_feature = "avx512vbmi",
+        target_feature = "avx512vbmi2"
+    )),
+    not(target_feature = "avx2"),
+    not(target_feature = "sse4.2")
+))]
+#[inline]
+pub(crate) fn kernels() -> &'static super::Kernels {
+    &super::fallback::KERNELS
+}
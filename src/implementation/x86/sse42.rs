@@ -2,15 +2,15 @@
 
 #[cfg(target_arch = "x86")]
 use core::arch::x86::{
-    __m128i, _mm_alignr_epi8, _mm_and_si128, _mm_loadu_si128, _mm_movemask_epi8, _mm_or_si128,
-    _mm_prefetch, _mm_set1_epi8, _mm_setr_epi8, _mm_setzero_si128, _mm_shuffle_epi8,
-    _mm_srli_epi16, _mm_subs_epu8, _mm_testz_si128, _mm_xor_si128, _MM_HINT_T0,
+    __m128i, _mm_alignr_epi8, _mm_and_si128, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8,
+    _mm_or_si128, _mm_prefetch, _mm_set1_epi8, _mm_setr_epi8, _mm_setzero_si128, _mm_shuffle_epi8,
+    _mm_srli_epi16, _mm_storeu_si128, _mm_subs_epu8, _mm_testz_si128, _mm_xor_si128, _MM_HINT_T0,
 };
 #[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::{
-    __m128i, _mm_alignr_epi8, _mm_and_si128, _mm_loadu_si128, _mm_movemask_epi8, _mm_or_si128,
-    _mm_prefetch, _mm_set1_epi8, _mm_setr_epi8, _mm_setzero_si128, _mm_shuffle_epi8,
-    _mm_srli_epi16, _mm_subs_epu8, _mm_testz_si128, _mm_xor_si128, _MM_HINT_T0,
+    __m128i, _mm_alignr_epi8, _mm_and_si128, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8,
+    _mm_or_si128, _mm_prefetch, _mm_set1_epi8, _mm_setr_epi8, _mm_setzero_si128, _mm_shuffle_epi8,
+    _mm_srli_epi16, _mm_storeu_si128, _mm_subs_epu8, _mm_testz_si128, _mm_xor_si128, _MM_HINT_T0,
 };
 
 use crate::implementation::helpers::Utf8CheckAlgorithm;
@@ -99,6 +99,13 @@ impl SimdU8Value {
         Self::from(_mm_loadu_si128(ptr.cast::<__m128i>()))
     }
 
+    #[flexpect::e(clippy::cast_ptr_alignment)]
+    #[target_feature(enable = "sse4.2")]
+    #[inline]
+    unsafe fn store_to(self, ptr: *mut u8) {
+        _mm_storeu_si128(ptr.cast::<__m128i>(), self.0);
+    }
+
     #[flexpect::e(clippy::too_many_arguments)]
     #[target_feature(enable = "sse4.2")]
     #[inline]
@@ -206,6 +213,19 @@ impl SimdU8Value {
     unsafe fn is_ascii(self) -> bool {
         _mm_movemask_epi8(self.0) == 0
     }
+
+    #[target_feature(enable = "sse4.2")]
+    #[inline]
+    unsafe fn cmp_eq(self, b: Self) -> Self {
+        Self::from(_mm_cmpeq_epi8(self.0, b.0))
+    }
+
+    #[flexpect::e(clippy::cast_sign_loss)]
+    #[target_feature(enable = "sse4.2")]
+    #[inline]
+    unsafe fn bitmask(self) -> u64 {
+        u64::from(_mm_movemask_epi8(self.0) as u32)
+    }
 }
 
 impl From<__m128i> for SimdU8Value {
@@ -0,0 +1,56 @@
+//! The `incremental` module revalidates UTF-8 buffers after in-place edits.
+//!
+//! If a buffer was valid UTF-8 before a byte range was replaced, only the bytes around the
+//! replacement can have become invalid. [`revalidate()`] checks just that window instead of the
+//! whole buffer:
+//!
+//! ```rust
+//! use simdutf8::incremental::revalidate;
+//!
+//! let mut buffer = "Grüße, world!".repeat(1000).into_bytes();
+//! // replace the "ü" in the 500th repetition with an "u"
+//! let pos = 500 * "Grüße, world!".len() + 2;
+//! buffer.splice(pos..pos + 2, b"u".iter().copied());
+//! assert!(revalidate(&buffer, pos..pos + 1).is_ok());
+//!
+//! // remove the second byte of the following "ß"
+//! buffer.remove(pos + 2);
+//! let err = revalidate(&buffer, pos + 2..pos + 2).unwrap_err();
+//! assert_eq!(err.valid_up_to(), pos + 1);
+//! ```
+
+use core::ops::Range;
+use core::str::from_utf8_unchecked;
+
+use crate::boundary::{ceil_char_boundary, floor_char_boundary};
+use crate::compat::{from_utf8, Utf8Error};
+
+/// Number of bytes around the edited range which might be part of a changed char.
+const CONTEXT_BYTES: usize = 3;
+
+/// Revalidates `buffer` after the bytes in `edited` have been replaced.
+///
+/// `buffer` is the buffer after the edit and `edited` is the range of the inserted bytes in it,
+/// which is empty for deletions. The rest of the buffer must have been valid UTF-8 before the
+/// edit. Only the edited range and up to three bytes on either side, extended to the enclosing
+/// char boundaries, are validated.
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the buffer is not valid UTF-8 after the edit. The error
+/// offsets are relative to the start of the buffer.
+///
+/// # Panics
+/// If `edited` is out of bounds or its start is larger than its end.
+#[inline]
+pub fn revalidate(buffer: &[u8], edited: Range<usize>) -> Result<&str, Utf8Error> {
+    assert!(
+        edited.start <= edited.end && edited.end <= buffer.len(),
+        "edited range out of bounds"
+    );
+    let start = floor_char_boundary(buffer, edited.start.saturating_sub(CONTEXT_BYTES));
+    let end = ceil_char_boundary(buffer, edited.end + CONTEXT_BYTES);
+    from_utf8(&buffer[start..end]).map_err(|err| err.offset_by(start))?;
+    // SAFETY: the window was just validated, the rest is unchanged and starts and ends at char
+    // boundaries of valid UTF-8
+    unsafe { Ok(from_utf8_unchecked(buffer)) }
+}
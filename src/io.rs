@@ -0,0 +1,479 @@
+//! The `io` module validates UTF-8 while reading.
+//!
+//! [`Utf8Reader`] returns validated chunks of any reader which end on char boundaries.
+//!
+//! [`read_to_string()`] and [`fs_read_to_string()`] replace [`std::io::read_to_string()`] and
+//! [`std::fs::read_to_string()`]. The data is validated after each read, so no separate pass over
+//! the whole input is necessary after the end of the input has been reached.
+//!
+//! [`read_line_simd()`] and [`lines_simd()`] replace [`BufRead::read_line()`] and
+//! [`BufRead::lines()`]. The newline is found and the line is validated in the same SIMD pass
+//! over the buffer of the reader:
+//!
+//! ```rust
+//! use simdutf8::io::{lines_simd, LineError};
+//!
+//! let input = &b"first\nsecond\r\nthird \xFF line\nlast"[..];
+//! let mut lines = lines_simd(input);
+//! assert_eq!(lines.next().unwrap().unwrap(), "first");
+//! assert_eq!(lines.next().unwrap().unwrap(), "second");
+//!
+//! let err = lines.next().unwrap().unwrap_err();
+//! let err = err.get_ref().unwrap().downcast_ref::<LineError>().unwrap();
+//! assert_eq!((err.line(), err.error().valid_up_to()), (3, 6));
+//!
+//! // the invalid line is skipped
+//! assert_eq!(lines.next().unwrap().unwrap(), "last");
+//! assert!(lines.next().is_none());
+//! ```
+
+use std::convert::TryFrom;
+use std::fmt::{Debug, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufRead, Read};
+use std::path::Path;
+use std::str::from_utf8_unchecked;
+
+use crate::compat::Utf8Error;
+use crate::implementation::stream::Utf8Stream;
+
+/// UTF-8 error of a line read with [`lines_simd()`].
+///
+/// Contains the line number and the [`Utf8Error`] with offsets relative to the start of the line.
+#[derive(Copy, Eq, PartialEq, Clone, Debug)]
+pub struct LineError {
+    line: usize,
+    error: Utf8Error,
+}
+
+impl LineError {
+    /// The number of the invalid line, starting at one.
+    #[inline]
+    #[must_use]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The validation error of the line, relative to the start of the line.
+    #[inline]
+    #[must_use]
+    pub fn error(&self) -> Utf8Error {
+        self.error
+    }
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for LineError {}
+
+/// Default buffer capacity of [`Utf8Reader`].
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+/// Maximum length of a UTF-8 char.
+const MAX_CHAR_LEN: usize = 4;
+
+/// Reads validated UTF-8 in chunks which end on char boundaries.
+///
+/// The chunks are borrowed from an internal buffer. An incomplete char at the end of a read is
+/// kept in the buffer and completed by the next read.
+///
+/// ```rust
+/// use simdutf8::io::Utf8Reader;
+///
+/// let mut reader = Utf8Reader::with_capacity(4, "Grüße".as_bytes());
+/// let mut chunks = Vec::new();
+/// loop {
+///     let chunk = reader.read_chunk().unwrap();
+///     if chunk.is_empty() {
+///         break;
+///     }
+///     chunks.push(chunk.to_owned());
+/// }
+/// assert_eq!(chunks, ["Grü", "ße"]);
+/// ```
+pub struct Utf8Reader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    end: usize,
+    filled: usize,
+    stream: Utf8Stream,
+    error: Option<Utf8Error>,
+}
+
+impl<R: Read> Utf8Reader<R> {
+    /// Creates a new reader with a buffer capacity of 8 KiB.
+    #[inline]
+    pub fn new(reader: R) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, reader)
+    }
+
+    /// Creates a new reader with the specified buffer capacity.
+    ///
+    /// # Panics
+    /// If `capacity` is smaller than four, the maximum length of a char.
+    #[inline]
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+        assert!(capacity >= MAX_CHAR_LEN, "capacity must be at least four");
+        Self {
+            reader,
+            buf: vec![0; capacity],
+            end: 0,
+            filled: 0,
+            stream: Utf8Stream::new(),
+            error: None,
+        }
+    }
+
+    /// Reads the next chunk.
+    ///
+    /// Returns a non-empty `str` ending on a char boundary or an empty `str` at the end of the
+    /// input.
+    ///
+    /// # Errors
+    /// Will return Err([`io::Error`]) if reading fails. If the input is not valid UTF-8, an error
+    /// of kind [`io::ErrorKind::InvalidData`] wrapping a [`Utf8Error`] is returned. Its offsets are
+    /// relative to the start of the input. All further calls return the same error.
+    #[inline]
+    pub fn read_chunk(&mut self) -> io::Result<&str> {
+        if let Some(error) = self.error {
+            return Err(invalid_data(error));
+        }
+        // move the incomplete char after the previous chunk to the front
+        self.buf.copy_within(self.end..self.filled, 0);
+        self.filled -= self.end;
+        self.end = 0;
+        loop {
+            let read = match self.reader.read(&mut self.buf[self.filled..]) {
+                Ok(read) => read,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            let res = if read == 0 {
+                self.stream.finish()
+            } else {
+                self.stream
+                    .update(&self.buf[self.filled..self.filled + read])
+            };
+            if let Err(error) = res {
+                self.error = Some(error);
+                return Err(invalid_data(error));
+            }
+            self.filled += read;
+            let end = self.filled - self.stream.pending_len();
+            if end > 0 || read == 0 {
+                self.end = end;
+                // SAFETY: the bytes up to `end` were validated and end on a char boundary
+                return unsafe { Ok(from_utf8_unchecked(&self.buf[..end])) };
+            }
+        }
+    }
+}
+
+impl<R> Utf8Reader<R> {
+    /// Returns a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Buffered data which has not been returned yet is lost.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[flexpect::e(clippy::missing_fields_in_debug)] // the buffer and validation state are omitted
+impl<R: Debug> Debug for Utf8Reader<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Utf8Reader")
+            .field("reader", &self.reader)
+            .field("capacity", &self.buf.len())
+            .finish()
+    }
+}
+
+/// Minimum number of bytes by which the buffer of [`read_to_string()`] grows.
+const MIN_READ_SIZE: usize = 32;
+
+/// Maximum number of bytes read at once by [`read_to_string()`], so that the zeroed buffer is
+/// still cached when it is read into and the read bytes when they are validated.
+const MAX_READ_SIZE: usize = 64 * 1024;
+
+#[inline]
+fn invalid_data(error: Utf8Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Reads all bytes into `buf`, which must be empty, and validates them after each read.
+fn read_to_string_inner<R: Read + ?Sized>(reader: &mut R, mut buf: Vec<u8>) -> io::Result<String> {
+    let mut stream = Utf8Stream::new();
+    // the bytes up to `buf.len()` are initialized, the bytes up to `len` are read
+    let mut len = 0;
+    loop {
+        if len == buf.capacity() {
+            buf.reserve(MIN_READ_SIZE);
+        }
+        let end = std::cmp::min(len + MAX_READ_SIZE, buf.capacity());
+        if buf.len() < end {
+            // each byte is zeroed once, right before it is read into
+            buf.resize(end, 0);
+        }
+        match reader.read(&mut buf[len..end]) {
+            Ok(0) => break,
+            Ok(read) => {
+                stream.update(&buf[len..len + read]).map_err(invalid_data)?;
+                len += read;
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    stream.finish().map_err(invalid_data)?;
+    buf.truncate(len);
+    // SAFETY: all bytes were validated after reading them
+    unsafe { Ok(String::from_utf8_unchecked(buf)) }
+}
+
+/// Reads all bytes of the reader into a new [`String`].
+///
+/// Analogue to [`std::io::read_to_string()`].
+///
+/// # Errors
+/// Will return Err([`io::Error`]) if reading fails. If the input is not valid UTF-8, an error of
+/// kind [`io::ErrorKind::InvalidData`] wrapping a [`Utf8Error`] is returned as soon as the
+/// invalid bytes have been read.
+#[inline]
+pub fn read_to_string<R: Read>(mut reader: R) -> io::Result<String> {
+    read_to_string_inner(&mut reader, Vec::new())
+}
+
+/// Reads the entire contents of a file into a [`String`].
+///
+/// Analogue to [`std::fs::read_to_string()`]. The buffer is allocated with the size of the file.
+///
+/// # Errors
+/// Will return Err([`io::Error`]) if opening or reading the file fails. If the contents are not
+/// valid UTF-8, an error of kind [`io::ErrorKind::InvalidData`] wrapping a [`Utf8Error`] is
+/// returned.
+#[inline]
+pub fn fs_read_to_string<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    // one more byte to detect the end of the file without growing the buffer
+    let capacity = file
+        .metadata()
+        .ok()
+        .and_then(|metadata| usize::try_from(metadata.len()).ok())
+        .map_or(0, |len| len.saturating_add(1));
+    read_to_string_inner(&mut file, Vec::with_capacity(capacity))
+}
+
+/// Validates `available` up to and including the first newline with `stream`.
+///
+/// Returns whether the newline was found, the number of bytes used and the validation result.
+/// The bytes are scanned once: only the rest of a char split between buffer refills and the bytes
+/// at an error are validated by the stream itself, everything else in the same pass which finds
+/// the newline.
+#[inline]
+fn validate_line_part(
+    stream: &mut Utf8Stream,
+    available: &[u8],
+) -> (bool, usize, Result<(), Utf8Error>) {
+    let mut pos = 0;
+    let missing = stream.missing_len();
+    if missing != 0 {
+        let (found, used, res) = validate_newline_bounded(stream, available, missing);
+        if found || res.is_err() || !stream.is_at_char_boundary() {
+            return (found, used, res);
+        }
+        pos = used;
+    }
+    let rest = &available[pos..];
+    match crate::implementation::validate_until_byte(rest, b'\n') {
+        Ok(end) => {
+            let found = end < rest.len();
+            let used = if found { end + 1 } else { end };
+            stream.skip_valid(used);
+            (found, pos + used, Ok(()))
+        }
+        Err(err) => {
+            // the stream reports the error relative to the start of the line, including an
+            // incomplete char before the newline, or carries an incomplete char at the end of
+            // the buffer over to the next refill
+            let valid = err.valid_up_to();
+            stream.skip_valid(valid);
+            let (found, used, res) = validate_newline_bounded(stream, &rest[valid..], 4);
+            (found, pos + valid + used, res)
+        }
+    }
+}
+
+/// Validates at most `max` bytes of `input` up to and including the first newline with `stream`.
+///
+/// Returns whether the newline was found, the number of bytes used and the validation result.
+#[inline]
+fn validate_newline_bounded(
+    stream: &mut Utf8Stream,
+    input: &[u8],
+    max: usize,
+) -> (bool, usize, Result<(), Utf8Error>) {
+    let bounded = &input[..core::cmp::min(max, input.len())];
+    let newline = bounded.iter().position(|&b| b == b'\n');
+    let used = newline.map_or(bounded.len(), |i| i + 1);
+    (newline.is_some(), used, stream.update(&bounded[..used]))
+}
+
+/// Reads a line including the newline and appends it to `buf` if it is valid UTF-8.
+///
+/// Invalid lines are consumed up to and including the newline but not appended. If reading
+/// fails, the bytes of the line read so far are kept in `buf` if they are valid UTF-8.
+fn read_line_inner<R: BufRead + ?Sized>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+) -> io::Result<Result<usize, Utf8Error>> {
+    let start = buf.len();
+    let mut stream = Utf8Stream::new();
+    let mut error = None;
+    let mut read = 0;
+    loop {
+        let (found, used) = {
+            let available = match reader.fill_buf() {
+                Ok(available) => available,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    if error.is_some() || !stream.is_at_char_boundary() {
+                        buf.truncate(start);
+                    }
+                    return Err(err);
+                }
+            };
+            if available.is_empty() {
+                break;
+            }
+            if error.is_some() {
+                let end = available.iter().position(|&b| b == b'\n');
+                end.map_or((false, available.len()), |end| (true, end + 1))
+            } else {
+                let (found, used, res) = validate_line_part(&mut stream, available);
+                match res {
+                    Ok(()) => buf.extend_from_slice(&available[..used]),
+                    Err(err) => error = Some(err),
+                }
+                (found, used)
+            }
+        };
+        reader.consume(used);
+        read += used;
+        if found {
+            break;
+        }
+    }
+    if error.is_none() {
+        error = stream.finish().err();
+    }
+    Ok(error.map_or(Ok(read), Err))
+}
+
+/// Truncates the buffer to its original length unless the appended bytes were validated.
+struct Guard<'a> {
+    buf: &'a mut Vec<u8>,
+    len: usize,
+}
+
+impl Drop for Guard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.buf.truncate(self.len);
+    }
+}
+
+/// Reads a line and appends it to `buf`, including the newline.
+///
+/// Analogue to [`BufRead::read_line()`]. Returns the number of bytes read, which is zero at the
+/// end of the input.
+///
+/// # Errors
+/// Will return Err([`io::Error`]) if reading fails, the bytes read before are appended to `buf`
+/// if they are valid UTF-8 as with [`BufRead::read_line()`]. If the line is not valid UTF-8, an
+/// error of kind [`io::ErrorKind::InvalidData`] wrapping a [`Utf8Error`] is returned. Its offsets
+/// are relative to the start of the line. The line is consumed but not appended to `buf` in that
+/// case.
+#[inline]
+pub fn read_line_simd<R: BufRead + ?Sized>(reader: &mut R, buf: &mut String) -> io::Result<usize> {
+    // SAFETY: the guard removes the appended bytes unless they were validated
+    let buf = unsafe { buf.as_mut_vec() };
+    let mut guard = Guard {
+        len: buf.len(),
+        buf,
+    };
+    match read_line_inner(reader, guard.buf) {
+        Ok(Ok(read)) => {
+            guard.len = guard.buf.len();
+            Ok(read)
+        }
+        Ok(Err(err)) => Err(invalid_data(err)),
+        Err(err) => {
+            // the bytes read before the error are kept if they are valid UTF-8
+            guard.len = guard.buf.len();
+            Err(err)
+        }
+    }
+}
+
+/// Returns an iterator over the lines of the reader.
+///
+/// Analogue to [`BufRead::lines()`]. The lines are returned without the newline and a carriage
+/// return preceding it.
+#[inline]
+pub fn lines_simd<R: BufRead>(reader: R) -> Lines<R> {
+    Lines { reader, line: 0 }
+}
+
+/// Iterator over the lines of a reader returned by [`lines_simd()`].
+///
+/// Lines which are not valid UTF-8 are returned as [`io::Error`] of kind
+/// [`io::ErrorKind::InvalidData`] wrapping a [`LineError`]. Iteration can continue with the next
+/// line after such an error.
+#[derive(Debug)]
+pub struct Lines<R> {
+    reader: R,
+    line: usize,
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = io::Result<String>;
+
+    #[inline]
+    fn next(&mut self) -> Option<io::Result<String>> {
+        let mut buf = Vec::new();
+        match read_line_inner(&mut self.reader, &mut buf) {
+            Ok(Ok(0)) => None,
+            Ok(Ok(_)) => {
+                self.line += 1;
+                if buf.last() == Some(&b'\n') {
+                    buf.pop();
+                    if buf.last() == Some(&b'\r') {
+                        buf.pop();
+                    }
+                }
+                // SAFETY: the line was validated
+                unsafe { Some(Ok(String::from_utf8_unchecked(buf))) }
+            }
+            Ok(Err(error)) => {
+                self.line += 1;
+                let error = LineError {
+                    line: self.line,
+                    error,
+                };
+                Some(Err(io::Error::new(io::ErrorKind::InvalidData, error)))
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
@@ -92,6 +92,71 @@
 //! an invalid UTF-8 sequence is encountered, it returns without processing the rest of the data.
 //! This comes at a slight performance penalty compared to the [`basic`] API even if the input is valid UTF-8.
 //!
+//! [`compat::validate_until()`] validates up to the first occurrence of a delimiter byte in the same pass which finds
+//! it, e.g. for line- and frame-based protocols.
+//!
+//! ### Diagnostics
+//! [`diagnostics::Report`] renders a [`compat::Utf8Error`] as a human-readable report with line, column and
+//! a hex dump of the offending bytes.
+//!
+//! ### ASCII
+//! The [`ascii`] module provides SIMD versions of `is_ascii()` and finding the first non-ASCII byte.
+//! [`ascii::classify()`] distinguishes between ASCII, UTF-8 and invalid input in a single scan.
+//!
+//! ### Char boundaries
+//! The [`boundary`] module finds char boundaries and provides validating helpers to truncate input to a byte budget,
+//! split it into pieces of limited size or resynchronize when starting to read in the middle of a char.
+//!
+//! ### Batch validation
+//! The [`batch`] module validates many short strings at once, packing several of them into one SIMD block.
+//!
+//! ### Incremental revalidation
+//! [`incremental::revalidate()`] checks a buffer that was valid UTF-8 after an in-place edit by only validating
+//! the bytes around the edited range.
+//!
+//! ### Merging chunk states
+//! [`merge::PartialState`] validates chunks of a stream independently, e.g. on different threads or machines, and
+//! merges the results in order into the same result as one sequential pass.
+//!
+//! ### Statistics
+//! [`stats::from_utf8_with_stats()`] and [`stats::Utf8StatsValidator`] count code points, the 1, 2, 3 and 4-byte
+//! sequences and newlines in the same pass which validates the input.
+//!
+//! ### Line index
+//! [`line_index::LineIndex`] finds the line starts in the same pass which validates the input and converts between
+//! byte offsets and line and UTF-16 column positions as used by the Language Server Protocol.
+//! [`line_index::LineIndexBuilder`] builds the index from streamed input. This requires the `std` feature.
+//!
+//! ### Char index
+//! [`char_index::CharIndex`] counts the chars of each 64-byte block in the same pass which validates the input, so that
+//! char indices and byte offsets of large strings can be converted without iterating over all chars. This requires the
+//! `std` feature.
+//!
+//! ### UTF-16 offsets
+//! The [`utf16`] module converts between UTF-8 byte offsets and UTF-16 code unit offsets as used by the Language Server
+//! Protocol and JavaScript, one at a time or in bulk.
+//!
+//! ### Block bit masks
+//! [`bitmap::BlockValidator`] validates the input and returns `u64` masks of the char starts and the bytes at which errors
+//! were detected for each 64-byte block, for SIMD parsers working with bit tricks. [`bitmap::validate_with_byte_bitmaps()`]
+//! finds up to 16 ASCII bytes like the structural characters of a format in the same pass which validates the input.
+//!
+//! ### C strings
+//! [`cstr::until_nul()`] finds the first NUL byte and validates the UTF-8 before it in a single pass.
+//!
+//! ### OS strings and paths
+//! On Unix, the [`os`] module converts [`std::ffi::OsStr`] and [`std::path::Path`] as well as their owned variants to
+//! UTF-8 strings. This requires the `std` feature.
+//!
+//! ### String builder
+//! [`builder::Utf8StringBuilder`] assembles a `String` from byte fragments, validating them as they are appended. This
+//! requires the `std` feature.
+//!
+//! ### Reading
+//! The [`io`] module validates UTF-8 while reading, e.g. [`io::lines_simd()`] is a replacement for
+//! [`std::io::BufRead::lines()`] and [`io::fs_read_to_string()`] for [`std::fs::read_to_string()`]. [`io::Utf8Reader`]
+//! returns validated chunks ending on char boundaries. This requires the `std` feature.
+//!
 //! ## Implementation selection
 //!
 //! ### X86
@@ -136,6 +201,26 @@
 //! See Validating UTF-8 In Less Than One Instruction Per Byte, Software: Practice and Experience 51 (5), 2021
 //! <https://arxiv.org/abs/2010.03090>
 
+pub mod ascii;
 pub mod basic;
+pub mod batch;
+pub mod bitmap;
+pub mod boundary;
+#[cfg(feature = "std")]
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod char_index;
 pub mod compat;
+pub mod cstr;
+pub mod diagnostics;
 mod implementation;
+pub mod incremental;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+pub mod line_index;
+pub mod merge;
+#[cfg(all(feature = "std", unix))]
+pub mod os;
+pub mod stats;
+pub mod utf16;
@@ -0,0 +1,280 @@
+//! The `line_index` module builds an index of line starts while validating UTF-8.
+//!
+//! The newlines are found in the same SIMD pass which validates the input. The index converts
+//! between byte offsets and positions given as line and UTF-16 column, as used by the
+//! [Language Server Protocol](https://microsoft.github.io/language-server-protocol/) with the
+//! default position encoding:
+//!
+//! ```rust
+//! use simdutf8::line_index::{LineIndex, LineIndexBuilder, Utf16Position};
+//!
+//! let text = "fn main() {\r\n    let 😊 = \"Grüße\";\n}\n";
+//! let index = LineIndex::new(text.as_bytes()).unwrap();
+//! assert_eq!(index.line_count(), 4);
+//! assert_eq!(index.line_starts(), &[0, 13, 39, 41]);
+//!
+//! let offset = text.find('=').unwrap();
+//! let position = index.to_utf16_position(text, offset).unwrap();
+//! // the emoji is encoded as a surrogate pair in UTF-16
+//! assert_eq!(position, Utf16Position { line: 1, column: 11 });
+//! assert_eq!(index.from_utf16_position(text, position), Some(offset));
+//!
+//! let mut builder = LineIndexBuilder::new();
+//! builder.update(b"a\nb\xC3").unwrap();
+//! builder.update(b"\xBC\nc").unwrap();
+//! assert_eq!(builder.finish().unwrap().line_starts(), &[0, 2, 6]);
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::ops::Range;
+
+use crate::compat::Utf8Error;
+use crate::implementation::stream::{FusedSink, Utf8Stream};
+use crate::utf16::{utf16_len, utf16_to_utf8_offset};
+
+/// Position in a text given as zero-based line and column in UTF-16 code units.
+///
+/// Corresponds to a `Position` of the Language Server Protocol with the `utf-16` position
+/// encoding.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utf16Position {
+    /// Zero-based line number.
+    pub line: usize,
+    /// Zero-based offset from the start of the line in UTF-16 code units.
+    pub column: usize,
+}
+
+/// Start offsets of the lines of valid UTF-8 text.
+///
+/// Lines are terminated by `'\n'`, a `'\r'` before it is part of the line terminator. A text
+/// ending with `'\n'` has an empty last line.
+#[derive(Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Start offsets of the lines, the first one is always zero.
+    starts: Vec<usize>,
+    /// Length of the text in bytes.
+    len: usize,
+}
+
+impl Default for LineIndex {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            starts: vec![0],
+            len: 0,
+        }
+    }
+}
+
+impl LineIndex {
+    /// Validates the input and builds the index of its lines in the same pass.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if the input is not valid UTF-8.
+    #[inline]
+    pub fn new(input: &[u8]) -> Result<Self, Utf8Error> {
+        let mut index = Self::default();
+        index.validate_and_push(input, 0)?;
+        index.len = input.len();
+        Ok(index)
+    }
+
+    /// Validates `input` at `offset` of the text and appends the starts of the lines after its
+    /// newlines.
+    #[inline]
+    fn validate_and_push(&mut self, input: &[u8], offset: usize) -> Result<(), Utf8Error> {
+        let starts = &mut self.starts;
+        crate::implementation::validate_with_byte_masks(
+            input,
+            b'\n',
+            &mut |block_start, mut mask| {
+                while mask != 0 {
+                    starts.push(offset + block_start + mask.trailing_zeros() as usize + 1);
+                    mask &= mask - 1;
+                }
+            },
+        )
+    }
+
+    /// Returns the length of the indexed text in bytes.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the indexed text is empty.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of lines, at least one.
+    #[inline]
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Returns the byte offsets of the starts of all lines.
+    #[inline]
+    #[must_use]
+    pub fn line_starts(&self) -> &[usize] {
+        &self.starts
+    }
+
+    /// Returns the line containing the byte at `offset`.
+    ///
+    /// The end of the text, `offset == len()`, belongs to the last line. Returns `None` if the
+    /// offset is after the end of the text.
+    #[inline]
+    #[must_use]
+    pub fn line_of(&self, offset: usize) -> Option<usize> {
+        if offset > self.len {
+            return None;
+        }
+        match self.starts.binary_search(&offset) {
+            Ok(line) => Some(line),
+            Err(next) => Some(next - 1),
+        }
+    }
+
+    /// Returns the byte range of `line` including its line terminator.
+    ///
+    /// Returns `None` if the line does not exist.
+    #[inline]
+    #[must_use]
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.starts.get(line)?;
+        let end = self.starts.get(line + 1).map_or(self.len, |&next| next);
+        Some(start..end)
+    }
+
+    /// Returns the byte range of `line` in `text` without its line terminator.
+    #[inline]
+    fn line_content<'a>(&self, text: &'a str, line: usize) -> Option<(usize, &'a str)> {
+        let range = self.line_range(line)?;
+        let start = range.start;
+        let mut content = text.get(range)?;
+        if content.ends_with('\n') {
+            content = &content[..content.len() - 1];
+            if content.ends_with('\r') {
+                content = &content[..content.len() - 1];
+            }
+        }
+        Some((start, content))
+    }
+
+    /// Converts a byte offset in `text` to a line and UTF-16 column.
+    ///
+    /// `text` must be the indexed text. Returns `None` if the offset is after the end of the
+    /// text or not at a char boundary.
+    #[inline]
+    #[must_use]
+    pub fn to_utf16_position(&self, text: &str, offset: usize) -> Option<Utf16Position> {
+        let line = self.line_of(offset)?;
+        let prefix = text.get(self.starts[line]..offset)?;
+        Some(Utf16Position {
+            line,
+            column: utf16_len(prefix),
+        })
+    }
+
+    /// Converts a line and UTF-16 column to a byte offset in `text`.
+    ///
+    /// `text` must be the indexed text. As in the Language Server Protocol, a column after the
+    /// end of the line refers to the end of the line before its terminator. A column in the
+    /// middle of a surrogate pair refers to the start of the char. Returns `None` if the line
+    /// does not exist.
+    #[inline]
+    #[must_use]
+    pub fn from_utf16_position(&self, text: &str, position: Utf16Position) -> Option<usize> {
+        let (start, content) = self.line_content(text, position.line)?;
+        if position.column >= utf16_len(content) {
+            return Some(start + content.len());
+        }
+        // a column in the middle of a surrogate pair follows the start of the char
+        let offset = utf16_to_utf8_offset(content, position.column)
+            .or_else(|| utf16_to_utf8_offset(content, position.column - 1))?;
+        Some(start + offset)
+    }
+}
+
+impl Debug for LineIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LineIndex")
+            .field("len", &self.len)
+            .field("line_count", &self.starts.len())
+            .finish()
+    }
+}
+
+impl FusedSink for LineIndex {
+    #[inline]
+    fn complete_char(&mut self, _ch: &[u8], _offset: usize) {
+        // a char completed across updates is never a newline
+    }
+
+    #[inline]
+    fn validate(&mut self, input: &[u8], offset: usize) -> bool {
+        self.validate_and_push(input, offset).is_ok()
+    }
+}
+
+/// Streaming builder of a [`LineIndex`], validating the input as it is added.
+///
+/// Chars may be split across updates.
+#[derive(Clone, Default)]
+pub struct LineIndexBuilder {
+    stream: Utf8Stream,
+    index: LineIndex,
+}
+
+impl LineIndexBuilder {
+    /// Creates a new builder.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates the next part of the text and adds its lines to the index.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if the input is not valid UTF-8. The error offsets are
+    /// relative to the start of the text. The state of the builder is unspecified afterwards.
+    #[inline]
+    pub fn update(&mut self, input: &[u8]) -> Result<(), Utf8Error> {
+        self.stream.update_fused(input, &mut self.index)?;
+        self.index.len += input.len();
+        Ok(())
+    }
+
+    /// Returns the index of the lines started so far.
+    #[inline]
+    #[must_use]
+    pub fn line_index(&self) -> &LineIndex {
+        &self.index
+    }
+
+    /// Finishes the index.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if the text ends with an incomplete char.
+    #[inline]
+    pub fn finish(self) -> Result<LineIndex, Utf8Error> {
+        self.stream.finish()?;
+        Ok(self.index)
+    }
+}
+
+#[flexpect::e(clippy::missing_fields_in_debug)] // the validation state is an internal detail
+impl Debug for LineIndexBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LineIndexBuilder")
+            .field("len", &self.index.len)
+            .field("line_count", &self.index.starts.len())
+            .finish()
+    }
+}
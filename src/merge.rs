@@ -0,0 +1,215 @@
+//! The `merge` module validates chunks of a stream independently and combines the results.
+//!
+//! [`PartialState::validate()`] summarizes a chunk without knowing the bytes before or after it.
+//! The summaries can be computed on different threads or machines and [merged](PartialState::merge)
+//! in stream order, in any grouping, into the same result as one sequential pass. Only the few
+//! bytes of chars crossing chunk boundaries are kept, so no chunk has to be read again:
+//!
+//! ```rust
+//! use simdutf8::merge::PartialState;
+//!
+//! let input = "Grüße, Jürgen ❤".repeat(100).into_bytes();
+//! // split at arbitrary byte positions, even in the middle of chars
+//! let shards: Vec<_> = input.chunks(7).map(PartialState::validate).collect();
+//! let state = shards
+//!     .into_iter()
+//!     .fold(PartialState::new(), PartialState::merge);
+//! assert!(state.finish().is_ok());
+//!
+//! let first = PartialState::validate(b"abc\xE2\x82");
+//! let second = PartialState::validate(b"\xFFdef");
+//! let err = first.merge(second).finish().unwrap_err();
+//! assert_eq!((err.valid_up_to(), err.error_len()), (3, Some(2)));
+//! ```
+
+use core::fmt::{Debug, Formatter};
+
+use crate::compat::{from_utf8, Utf8Error};
+use crate::implementation::helpers::is_continuation_byte;
+
+/// Maximum number of bytes of a char before or after a chunk boundary.
+const MAX_PARTIAL_LEN: usize = 3;
+
+/// Validation state of a chunk of a stream, independent of the bytes around it.
+///
+/// The empty state returned by [`PartialState::new()`] is the identity of
+/// [`merge()`](Self::merge).
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
+pub struct PartialState {
+    /// Length of the chunk.
+    len: usize,
+    /// Leading continuation bytes, which must complete a char of the previous chunk.
+    head: [u8; MAX_PARTIAL_LEN],
+    head_len: usize,
+    /// Incomplete char at the end, which must be completed by the next chunk.
+    tail: [u8; MAX_PARTIAL_LEN],
+    tail_len: usize,
+    /// `false` if the chunk consists of the head only.
+    closed: bool,
+    /// First error after the head, relative to the start of the chunk.
+    error: Option<Utf8Error>,
+}
+
+impl PartialState {
+    /// Creates the state of an empty chunk.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates a chunk which can start and end anywhere in the stream.
+    #[inline]
+    #[must_use]
+    pub fn validate(chunk: &[u8]) -> Self {
+        let head_len = chunk
+            .iter()
+            .take(MAX_PARTIAL_LEN)
+            .take_while(|&&b| is_continuation_byte(b))
+            .count();
+        let mut state = Self {
+            len: chunk.len(),
+            head_len,
+            closed: head_len < chunk.len(),
+            ..Self::default()
+        };
+        state.head[..head_len].copy_from_slice(&chunk[..head_len]);
+        match from_utf8(&chunk[head_len..]) {
+            Ok(_) => {}
+            Err(err) if err.error_len().is_some() => state.error = Some(err.offset_by(head_len)),
+            Err(err) => {
+                let tail = &chunk[head_len + err.valid_up_to()..];
+                state.tail[..tail.len()].copy_from_slice(tail);
+                state.tail_len = tail.len();
+            }
+        }
+        state
+    }
+
+    /// Combines the state with the one of the chunk directly following it.
+    ///
+    /// Merging is associative: `a.merge(b).merge(c)` equals `a.merge(b.merge(c))`.
+    #[inline]
+    #[must_use]
+    pub fn merge(self, next: Self) -> Self {
+        let len = self.len + next.len;
+        if self.error.is_some() {
+            return Self { len, ..self };
+        }
+        if !self.closed {
+            return self.merge_head(next);
+        }
+        let mut merged = Self {
+            len,
+            closed: true,
+            tail: [0; MAX_PARTIAL_LEN],
+            tail_len: 0,
+            ..self
+        };
+        // the char crossing the boundary, followed by a placeholder for the first char after the
+        // head of a closed chunk
+        let mut junction = [b' '; 2 * MAX_PARTIAL_LEN + 1];
+        junction[..self.tail_len].copy_from_slice(&self.tail[..self.tail_len]);
+        let head_end = self.tail_len + next.head_len;
+        junction[self.tail_len..head_end].copy_from_slice(&next.head[..next.head_len]);
+        let junction_start = self.len - self.tail_len;
+        let junction_len = if next.closed { head_end + 1 } else { head_end };
+        match from_utf8(&junction[..junction_len]) {
+            Ok(_) => {}
+            Err(err) if err.error_len().is_some() => {
+                merged.error = Some(err.offset_by(junction_start));
+                return merged;
+            }
+            Err(err) => {
+                // only possible if the next chunk is not closed
+                let tail = &junction[err.valid_up_to()..junction_len];
+                merged.tail[..tail.len()].copy_from_slice(tail);
+                merged.tail_len = tail.len();
+                return merged;
+            }
+        }
+        if next.closed {
+            merged.error = next.error.map(|err| err.offset_by(self.len));
+            merged.tail = next.tail;
+            merged.tail_len = next.tail_len;
+        }
+        merged
+    }
+
+    /// Merges a state consisting of the head only with the following state.
+    #[inline]
+    fn merge_head(self, next: Self) -> Self {
+        let run_len = self.head_len + next.head_len;
+        let mut merged = Self {
+            len: self.len + next.len,
+            closed: next.closed || run_len > MAX_PARTIAL_LEN,
+            error: next.error.map(|err| err.offset_by(self.len)),
+            ..next
+        };
+        merged.head[..self.head_len].copy_from_slice(&self.head[..self.head_len]);
+        let head_len = core::cmp::min(run_len, MAX_PARTIAL_LEN);
+        merged.head[self.head_len..head_len]
+            .copy_from_slice(&next.head[..head_len - self.head_len]);
+        merged.head_len = head_len;
+        if run_len > MAX_PARTIAL_LEN {
+            // more continuation bytes than any char before can take
+            merged.error = Some(Utf8Error {
+                valid_up_to: MAX_PARTIAL_LEN,
+                error_len: Some(1),
+            });
+            merged.tail = [0; MAX_PARTIAL_LEN];
+            merged.tail_len = 0;
+        }
+        merged
+    }
+
+    /// Returns the number of bytes validated.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no bytes were validated.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Finishes validation, treating the state as the one of the whole stream.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if the stream is not valid UTF-8. The error is the same as
+    /// the one returned by [`from_utf8()`] for the whole stream.
+    #[inline]
+    pub fn finish(&self) -> Result<(), Utf8Error> {
+        if self.head_len != 0 {
+            // continuation bytes at the start of the stream
+            return Err(Utf8Error {
+                valid_up_to: 0,
+                error_len: Some(1),
+            });
+        }
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        if self.tail_len != 0 {
+            return Err(Utf8Error {
+                valid_up_to: self.len - self.tail_len,
+                error_len: None,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[flexpect::e(clippy::missing_fields_in_debug)] // the boundary bytes are an internal detail
+impl Debug for PartialState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PartialState")
+            .field("len", &self.len)
+            .field("error", &self.error)
+            .finish()
+    }
+}
@@ -0,0 +1,118 @@
+//! The `os` module converts OS strings and paths to UTF-8 strings on Unix.
+//!
+//! On Unix, [`OsStr`] and [`Path`] are arbitrary byte sequences. [`OsStr::to_str()`] and
+//! [`Path::to_str()`] validate them with the standard library. The functions in this module use
+//! the SIMD implementation instead and report the position of invalid bytes with a
+//! [`compat::Utf8Error`]:
+//!
+//! ```rust
+//! use std::ffi::OsStr;
+//! use std::os::unix::ffi::OsStrExt;
+//! use std::path::Path;
+//! use simdutf8::os::{os_str_to_str, path_to_str};
+//!
+//! assert_eq!(path_to_str(Path::new("/tmp/grüße.txt")).unwrap(), "/tmp/grüße.txt");
+//! let err = os_str_to_str(OsStr::from_bytes(b"bad\xFFname")).unwrap_err();
+//! assert_eq!(err.valid_up_to(), 3);
+//! ```
+
+use std::ffi::{OsStr, OsString};
+use std::fmt::{Debug, Display, Formatter};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+
+use crate::compat::{self, Utf8Error};
+
+/// Error of the conversion of an owned OS string or path to a [`String`].
+///
+/// Contains the [`compat::Utf8Error`] and gives back the value which could not be converted.
+#[derive(Clone, Debug)]
+pub struct IntoStringError<T> {
+    value: T,
+    error: Utf8Error,
+}
+
+impl<T> IntoStringError<T> {
+    /// The validation error.
+    #[inline]
+    #[must_use]
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+
+    /// The value which could not be converted.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Display for IntoStringError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl<T: Debug> std::error::Error for IntoStringError<T> {}
+
+/// Validates the bytes of the input, using the fast [`basic`](crate::basic) validation first.
+#[inline]
+fn validate(input: &[u8]) -> Result<&str, Utf8Error> {
+    crate::basic::from_utf8(input).or_else(|_| compat::from_utf8(input))
+}
+
+/// Converts an [`OsStr`] to `str`.
+///
+/// Analogue to [`OsStr::to_str()`].
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the input is not valid UTF-8.
+#[inline]
+pub fn os_str_to_str(input: &OsStr) -> Result<&str, Utf8Error> {
+    validate(input.as_bytes())
+}
+
+/// Converts a [`Path`] to `str`.
+///
+/// Analogue to [`Path::to_str()`].
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the path is not valid UTF-8.
+#[inline]
+pub fn path_to_str(input: &Path) -> Result<&str, Utf8Error> {
+    os_str_to_str(input.as_os_str())
+}
+
+/// Converts an [`OsString`] to [`String`] without copying.
+///
+/// Analogue to [`OsString::into_string()`].
+///
+/// # Errors
+/// Will return Err([`IntoStringError`]) with the passed [`OsString`] if it is not valid UTF-8.
+#[inline]
+pub fn os_string_into_string(input: OsString) -> Result<String, IntoStringError<OsString>> {
+    let bytes = input.into_vec();
+    match validate(&bytes) {
+        // SAFETY: the bytes were just validated
+        Ok(_) => unsafe { Ok(String::from_utf8_unchecked(bytes)) },
+        Err(error) => Err(IntoStringError {
+            value: OsString::from_vec(bytes),
+            error,
+        }),
+    }
+}
+
+/// Converts a [`PathBuf`] to [`String`] without copying.
+///
+/// Analogue to `path.into_os_string().into_string()`.
+///
+/// # Errors
+/// Will return Err([`IntoStringError`]) with the passed [`PathBuf`] if it is not valid UTF-8.
+#[inline]
+pub fn path_buf_into_string(input: PathBuf) -> Result<String, IntoStringError<PathBuf>> {
+    os_string_into_string(input.into_os_string()).map_err(|err| IntoStringError {
+        value: PathBuf::from(err.value),
+        error: err.error,
+    })
+}
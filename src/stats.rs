@@ -0,0 +1,237 @@
+//! The `stats` module collects statistics about UTF-8 text while validating it.
+//!
+//! The number of code points, the number of 1, 2, 3 and 4-byte sequences and the number of
+//! newlines are counted in the same SIMD pass which validates the input:
+//!
+//! ```rust
+//! use simdutf8::stats::{from_utf8_with_stats, Utf8StatsValidator};
+//!
+//! let (text, stats) = from_utf8_with_stats("Grüße\n❤\n😊!".as_bytes()).unwrap();
+//! assert_eq!(stats.code_points(), text.chars().count());
+//! assert_eq!(stats.ascii(), 6);
+//! assert_eq!(stats.two_byte(), 2);
+//! assert_eq!(stats.three_byte(), 1);
+//! assert_eq!(stats.four_byte(), 1);
+//! assert_eq!(stats.newlines(), 2);
+//! assert_eq!(stats.ascii_ratio(), 0.6);
+//! assert_eq!(stats.utf16_len(), text.encode_utf16().count());
+//!
+//! let mut validator = Utf8StatsValidator::new();
+//! validator.update(b"Gr\xC3").unwrap();
+//! validator.update(b"\xBC\xC3\x9Fe").unwrap();
+//! assert_eq!(validator.finish().unwrap().code_points(), 5);
+//! ```
+
+use core::fmt::{Debug, Formatter};
+
+use crate::compat::Utf8Error;
+use crate::implementation::stream::{FusedSink, Utf8Stream};
+
+/// Statistics of valid UTF-8 text.
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub struct Utf8Stats {
+    pub(crate) len: usize,
+    pub(crate) ascii: usize,
+    /// Number of lead bytes of 2, 3 or 4-byte sequences.
+    pub(crate) lead2: usize,
+    /// Number of lead bytes of 3 or 4-byte sequences.
+    pub(crate) lead3: usize,
+    /// Number of lead bytes of 4-byte sequences.
+    pub(crate) lead4: usize,
+    pub(crate) newlines: usize,
+}
+
+impl Utf8Stats {
+    /// Adds the byte classes of `input` without validating it.
+    #[inline]
+    pub(crate) fn count_scalar(&mut self, input: &[u8]) {
+        self.len += input.len();
+        for &b in input {
+            self.ascii += usize::from(b < 0x80);
+            self.lead2 += usize::from(b >= 0xC0);
+            self.lead3 += usize::from(b >= 0xE0);
+            self.lead4 += usize::from(b >= 0xF0);
+            self.newlines += usize::from(b == b'\n');
+        }
+    }
+
+    /// Adds the counts of `other`.
+    #[inline]
+    pub(crate) fn add(&mut self, other: &Self) {
+        self.len += other.len;
+        self.ascii += other.ascii;
+        self.lead2 += other.lead2;
+        self.lead3 += other.lead3;
+        self.lead4 += other.lead4;
+        self.newlines += other.newlines;
+    }
+
+    /// Returns the length of the text in bytes.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the text is empty.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of code points, i.e. the number of chars.
+    #[inline]
+    #[must_use]
+    pub fn code_points(&self) -> usize {
+        self.ascii + self.lead2
+    }
+
+    /// Returns the number of ASCII chars, the 1-byte sequences.
+    #[inline]
+    #[must_use]
+    pub fn ascii(&self) -> usize {
+        self.ascii
+    }
+
+    /// Returns the number of 2-byte sequences.
+    #[inline]
+    #[must_use]
+    pub fn two_byte(&self) -> usize {
+        self.lead2 - self.lead3
+    }
+
+    /// Returns the number of 3-byte sequences.
+    #[inline]
+    #[must_use]
+    pub fn three_byte(&self) -> usize {
+        self.lead3 - self.lead4
+    }
+
+    /// Returns the number of 4-byte sequences.
+    #[inline]
+    #[must_use]
+    pub fn four_byte(&self) -> usize {
+        self.lead4
+    }
+
+    /// Returns the number of newline (`\n`) bytes.
+    #[inline]
+    #[must_use]
+    pub fn newlines(&self) -> usize {
+        self.newlines
+    }
+
+    /// Returns the share of ASCII chars in all chars, `1.0` for empty text.
+    #[flexpect::e(clippy::cast_precision_loss)] // a ratio does not need to be exact
+    #[inline]
+    #[must_use]
+    pub fn ascii_ratio(&self) -> f64 {
+        if self.code_points() == 0 {
+            1.0
+        } else {
+            self.ascii as f64 / self.code_points() as f64
+        }
+    }
+
+    /// Returns the number of UTF-16 code units needed to encode the text.
+    ///
+    /// 4-byte sequences are encoded as surrogate pairs.
+    #[inline]
+    #[must_use]
+    pub fn utf16_len(&self) -> usize {
+        self.code_points() + self.lead4
+    }
+}
+
+#[flexpect::e(clippy::missing_fields_in_debug)] // the cumulative lead byte counts are shown per length
+impl Debug for Utf8Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Utf8Stats")
+            .field("len", &self.len)
+            .field("ascii", &self.ascii)
+            .field("two_byte", &self.two_byte())
+            .field("three_byte", &self.three_byte())
+            .field("four_byte", &self.four_byte())
+            .field("newlines", &self.newlines)
+            .finish()
+    }
+}
+
+impl FusedSink for Utf8Stats {
+    #[inline]
+    fn complete_char(&mut self, ch: &[u8], _offset: usize) {
+        self.count_scalar(ch);
+    }
+
+    #[inline]
+    fn validate(&mut self, input: &[u8], _offset: usize) -> bool {
+        crate::implementation::validate_with_stats(input, self).is_ok()
+    }
+}
+
+/// Validates the input and collects its statistics in the same pass.
+///
+/// # Errors
+/// Will return Err([`Utf8Error`]) if the input is not valid UTF-8.
+#[inline]
+pub fn from_utf8_with_stats(input: &[u8]) -> Result<(&str, Utf8Stats), Utf8Error> {
+    let mut stats = Utf8Stats::default();
+    crate::implementation::validate_with_stats(input, &mut stats)?;
+    // SAFETY: the input was just validated
+    unsafe { Ok((core::str::from_utf8_unchecked(input), stats)) }
+}
+
+/// Streaming validator which collects the statistics of the validated text.
+///
+/// Chars may be split across updates.
+#[derive(Clone, Default)]
+pub struct Utf8StatsValidator {
+    stream: Utf8Stream,
+    stats: Utf8Stats,
+}
+
+impl Utf8StatsValidator {
+    /// Creates a new validator.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates the next part of the input.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if the input is not valid UTF-8. The error offsets are
+    /// relative to the start of the input. The state of the validator is unspecified afterwards.
+    #[inline]
+    pub fn update(&mut self, input: &[u8]) -> Result<(), Utf8Error> {
+        self.stream.update_fused(input, &mut self.stats)
+    }
+
+    /// Returns the statistics of the complete chars validated so far.
+    #[inline]
+    #[must_use]
+    pub fn stats(&self) -> Utf8Stats {
+        self.stats
+    }
+
+    /// Finishes validation and returns the statistics of the whole input.
+    ///
+    /// # Errors
+    /// Will return Err([`Utf8Error`]) if the input ends with an incomplete char.
+    #[inline]
+    pub fn finish(self) -> Result<Utf8Stats, Utf8Error> {
+        self.stream.finish()?;
+        Ok(self.stats)
+    }
+}
+
+#[flexpect::e(clippy::missing_fields_in_debug)] // the validation state is an internal detail
+impl Debug for Utf8StatsValidator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Utf8StatsValidator")
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
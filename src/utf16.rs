@@ -0,0 +1,146 @@
+//! The `utf16` module converts between UTF-8 byte offsets and UTF-16 code unit offsets.
+//!
+//! Protocols like the Language Server Protocol and JavaScript strings address text in UTF-16
+//! code units. Chars encoded with up to three bytes in UTF-8 take one code unit, chars encoded
+//! with four bytes take two code units, a surrogate pair. The code units are counted with SIMD
+//! instructions, 64 bytes at a time:
+//!
+//! ```rust
+//! use simdutf8::utf16::{utf16_len, utf16_to_utf8_offset, utf8_to_utf16_offset};
+//!
+//! let text = "a😊b€c";
+//! assert_eq!(utf16_len(text), 6);
+//! assert_eq!(utf8_to_utf16_offset(text, 5), Some(3));
+//! assert_eq!(utf16_to_utf8_offset(text, 3), Some(5));
+//! // in the middle of the surrogate pair
+//! assert_eq!(utf16_to_utf8_offset(text, 2), None);
+//! ```
+//!
+//! Many offsets are converted with [`utf8_to_utf16_offsets()`] and [`utf16_to_utf8_offsets()`],
+//! which only count the code units between successive offsets.
+
+use crate::implementation::helpers::is_continuation_byte;
+use crate::implementation::{count_utf16_units, skip_utf16_units};
+
+/// Returns the number of UTF-16 code units needed to encode `text`.
+///
+/// Analogue to `text.encode_utf16().count()`.
+#[inline]
+#[must_use]
+pub fn utf16_len(text: &str) -> usize {
+    count_utf16_units(text.as_bytes())
+}
+
+/// Converts a byte offset in `text` to the corresponding UTF-16 code unit offset.
+///
+/// Returns `None` if the offset is after the end of the text or not at a char boundary.
+#[inline]
+#[must_use]
+pub fn utf8_to_utf16_offset(text: &str, offset: usize) -> Option<usize> {
+    if text.is_char_boundary(offset) {
+        Some(utf16_len(&text[..offset]))
+    } else {
+        None
+    }
+}
+
+/// Converts a UTF-16 code unit offset in `text` to the corresponding byte offset.
+///
+/// Returns `None` if the offset is after the end of the text or in the middle of a surrogate
+/// pair.
+#[inline]
+#[must_use]
+pub fn utf16_to_utf8_offset(text: &str, utf16_offset: usize) -> Option<usize> {
+    utf16_to_utf8_offset_from(text, (0, 0), utf16_offset)
+}
+
+/// Converts a UTF-16 code unit offset starting the search at `anchor`, a byte offset at a char
+/// boundary and the corresponding code unit offset which must not be after `utf16_offset`.
+#[inline]
+fn utf16_to_utf8_offset_from(
+    text: &str,
+    anchor: (usize, usize),
+    utf16_offset: usize,
+) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let (skipped, skipped_units) = skip_utf16_units(&bytes[anchor.0..], utf16_offset - anchor.1);
+    let mut pos = anchor.0 + skipped;
+    let mut units = anchor.1 + skipped_units;
+    // the rest of a char starting in the skipped blocks is already counted
+    while pos < bytes.len() && is_continuation_byte(bytes[pos]) {
+        pos += 1;
+    }
+    for c in text[pos..].chars() {
+        if units >= utf16_offset {
+            break;
+        }
+        units += c.len_utf16();
+        pos += c.len_utf8();
+    }
+    if units == utf16_offset {
+        Some(pos)
+    } else {
+        None
+    }
+}
+
+/// Converts byte offsets in `text` to UTF-16 code unit offsets and stores them in the
+/// corresponding elements of `results`, which must have the same length as `offsets`.
+///
+/// Only the code units between successive offsets are counted, so the conversion is fastest if
+/// the offsets are sorted. An element is `None` if the offset is after the end of the text or
+/// not at a char boundary.
+///
+/// # Panics
+/// Panics if `offsets` and `results` differ in length.
+#[inline]
+pub fn utf8_to_utf16_offsets(text: &str, offsets: &[usize], results: &mut [Option<usize>]) {
+    assert_eq!(
+        offsets.len(),
+        results.len(),
+        "offsets and results must have the same length"
+    );
+    let bytes = text.as_bytes();
+    let mut prev = (0, 0);
+    for (&offset, result) in offsets.iter().zip(results.iter_mut()) {
+        if !text.is_char_boundary(offset) {
+            *result = None;
+            continue;
+        }
+        let units = if offset >= prev.0 {
+            prev.1 + count_utf16_units(&bytes[prev.0..offset])
+        } else {
+            prev.1 - count_utf16_units(&bytes[offset..prev.0])
+        };
+        prev = (offset, units);
+        *result = Some(units);
+    }
+}
+
+/// Converts UTF-16 code unit offsets in `text` to byte offsets and stores them in the
+/// corresponding elements of `results`, which must have the same length as `utf16_offsets`.
+///
+/// Each conversion continues at the previous offset if it is not before it, so the conversion
+/// is fastest if the offsets are sorted. An element is `None` if the offset is after the end of
+/// the text or in the middle of a surrogate pair.
+///
+/// # Panics
+/// Panics if `utf16_offsets` and `results` differ in length.
+#[inline]
+pub fn utf16_to_utf8_offsets(text: &str, utf16_offsets: &[usize], results: &mut [Option<usize>]) {
+    assert_eq!(
+        utf16_offsets.len(),
+        results.len(),
+        "offsets and results must have the same length"
+    );
+    let mut anchor = (0, 0);
+    for (&utf16_offset, result) in utf16_offsets.iter().zip(results.iter_mut()) {
+        if utf16_offset < anchor.1 {
+            anchor = (0, 0);
+        }
+        *result = utf16_to_utf8_offset_from(text, anchor, utf16_offset);
+        if let Some(offset) = *result {
+            anchor = (offset, utf16_offset);
+        }
+    }
+}
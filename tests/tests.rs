@@ -44,10 +44,47 @@ fn test_valid(input: &[u8]) {
     assert!(basic_from_utf8_mut(mut_input.as_mut_slice()).is_ok());
     assert!(compat_from_utf8_mut(mut_input.as_mut_slice()).is_ok());
 
+    let mut dst = vec![std::mem::MaybeUninit::<u8>::uninit(); input.len() + 1];
+    assert!(simdutf8::basic::validate_and_copy(input, &mut dst).is_ok());
+    let copy = simdutf8::compat::validate_and_copy(input, &mut dst).unwrap();
+    assert_eq!(copy.as_bytes(), input);
+
+    test_stats_valid(input);
+
     #[cfg(feature = "public_imp")]
     public_imp::test_valid(input);
 }
 
+fn test_stats_valid(input: &[u8]) {
+    use simdutf8::stats::{from_utf8_with_stats, Utf8StatsValidator};
+
+    let text = std::str::from_utf8(input).unwrap();
+    let (res, stats) = from_utf8_with_stats(input).unwrap();
+    assert_eq!(res, text);
+    assert_eq!(stats.len(), text.len());
+    assert_eq!(stats.code_points(), text.chars().count());
+    for len in 1..=4 {
+        let expected = text.chars().filter(|c| c.len_utf8() == len).count();
+        let count = [
+            stats.ascii(),
+            stats.two_byte(),
+            stats.three_byte(),
+            stats.four_byte(),
+        ];
+        assert_eq!(count[len - 1], expected);
+    }
+    assert_eq!(stats.newlines(), text.matches('\n').count());
+    assert_eq!(stats.utf16_len(), text.encode_utf16().count());
+
+    for &chunk_size in &[1, 3, 64, 100] {
+        let mut validator = Utf8StatsValidator::new();
+        for chunk in input.chunks(chunk_size) {
+            validator.update(chunk).unwrap();
+        }
+        assert_eq!(validator.finish().unwrap(), stats);
+    }
+}
+
 fn test_invalid(input: &[u8], valid_up_to: usize, error_len: Option<usize>) {
     // std lib sanity check
     let err = std::str::from_utf8(input).unwrap_err();
@@ -59,6 +96,27 @@ fn test_invalid(input: &[u8], valid_up_to: usize, error_len: Option<usize>) {
     assert_eq!(err.valid_up_to(), valid_up_to);
     assert_eq!(err.error_len(), error_len);
 
+    let mut dst = vec![std::mem::MaybeUninit::<u8>::uninit(); input.len()];
+    assert!(simdutf8::basic::validate_and_copy(input, &mut dst).is_err());
+    let err = simdutf8::compat::validate_and_copy(input, &mut dst).unwrap_err();
+    assert_eq!(err.valid_up_to(), valid_up_to);
+    assert_eq!(err.error_len(), error_len);
+
+    let err = simdutf8::stats::from_utf8_with_stats(input).unwrap_err();
+    assert_eq!(err.valid_up_to(), valid_up_to);
+    assert_eq!(err.error_len(), error_len);
+    for &chunk_size in &[1, 3, 64, 100] {
+        let mut validator = simdutf8::stats::Utf8StatsValidator::new();
+        let err = input
+            .chunks(chunk_size)
+            .map(|chunk| validator.update(chunk))
+            .find(Result::is_err)
+            .unwrap_or_else(|| validator.clone().finish().map(drop))
+            .unwrap_err();
+        assert_eq!(err.valid_up_to(), valid_up_to);
+        assert_eq!(err.error_len(), error_len);
+    }
+
     #[cfg(feature = "public_imp")]
     public_imp::test_invalid(input, valid_up_to, error_len);
 }
@@ -66,8 +124,8 @@ fn test_invalid(input: &[u8], valid_up_to: usize, error_len: Option<usize>) {
 #[cfg(feature = "public_imp")]
 mod public_imp {
 
-    #[allow(unused_variables)] // nothing to do if not SIMD implementation is available
     pub(super) fn test_valid(input: &[u8]) {
+        test_streaming::<simdutf8::basic::imp::fallback::Utf8ValidatorImp>(input, true);
         if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
             #[cfg(all(
                 avx512_stable,
@@ -154,6 +212,7 @@ mod public_imp {
 
     #[allow(unused_variables)] // nothing to do if not SIMD implementation is available
     pub(super) fn test_invalid(input: &[u8], valid_up_to: usize, error_len: Option<usize>) {
+        test_streaming::<simdutf8::basic::imp::fallback::Utf8ValidatorImp>(input, false);
         if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
             #[cfg(all(
                 avx512_stable,
@@ -277,6 +336,112 @@ mod public_imp {
         }
     }
 
+    #[allow(unused)] // not used if not SIMD implementation is available
+    fn test_snapshot_roundtrip<T: simdutf8::basic::imp::Utf8ValidatorSnapshot + Clone>() {
+        let long = "Grüße, Jürgen ❤ 😊".repeat(10);
+        let mut invalid = long.clone().into_bytes();
+        invalid[100] = 0xFF;
+        let inputs: [&[u8]; 3] = [long.as_bytes(), &invalid, b"abc\xE2\x82"];
+        for input in inputs.iter() {
+            let ok = std::str::from_utf8(input).is_ok();
+            for block_size in [1, 2, 3, 36, 64, 99].iter() {
+                for split in (0..=input.len()).step_by(*block_size) {
+                    unsafe {
+                        let mut validator = T::new();
+                        validator.update(&input[..split]);
+                        // continue from a serialized snapshot and check the clone separately
+                        let mut clone = validator.clone();
+                        let bytes = validator.snapshot().to_bytes();
+                        let state = simdutf8::basic::imp::Utf8ValidatorState::from_bytes(&bytes);
+                        let mut validator = T::restore(&state.unwrap());
+                        for chunk in input[split..].chunks(*block_size) {
+                            validator.update(chunk);
+                        }
+                        assert_eq!(validator.finalize().is_ok(), ok);
+                        clone.update(&input[split..]);
+                        assert_eq!(clone.finalize().is_ok(), ok);
+                    }
+                }
+            }
+        }
+    }
+
+    #[allow(unused)] // not used if not SIMD implementation is available
+    fn test_snapshot_between<
+        S: simdutf8::basic::imp::Utf8ValidatorSnapshot,
+        R: simdutf8::basic::imp::Utf8ValidatorSnapshot,
+    >() {
+        let long = "Grüße, Jürgen ❤ 😊".repeat(10);
+        let inputs: [&[u8]; 4] = [
+            long.as_bytes(),
+            b"a\xF0\x9F\x98\x8Ab",
+            b"a\xED\xA0\x80b",
+            b"abc\xE2\x82",
+        ];
+        for input in inputs.iter() {
+            let ok = std::str::from_utf8(input).is_ok();
+            for split in 0..=input.len() {
+                unsafe {
+                    let mut validator = S::new();
+                    validator.update(&input[..split]);
+                    let mut validator = R::restore(&validator.snapshot());
+                    validator.update(&input[split..]);
+                    assert_eq!(validator.finalize().is_ok(), ok, "{:x?} {}", input, split);
+                }
+            }
+        }
+        assert!(simdutf8::basic::imp::Utf8ValidatorState::from_bytes(&[0; 70]).is_none());
+    }
+
+    #[test]
+    fn test_snapshot_fallback() {
+        use simdutf8::basic::imp::fallback;
+
+        test_snapshot_roundtrip::<fallback::Utf8ValidatorImp>();
+        test_snapshot_between::<fallback::Utf8ValidatorImp, fallback::Utf8ValidatorImp>();
+    }
+
+    #[test]
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn test_snapshot_x86() {
+        use simdutf8::basic::imp::fallback;
+        use simdutf8::basic::imp::x86::{avx2, sse42};
+
+        if std::is_x86_feature_detected!("sse4.2") {
+            test_snapshot_roundtrip::<sse42::Utf8ValidatorImp>();
+            test_snapshot_between::<sse42::Utf8ValidatorImp, fallback::Utf8ValidatorImp>();
+            test_snapshot_between::<fallback::Utf8ValidatorImp, sse42::Utf8ValidatorImp>();
+        }
+        if std::is_x86_feature_detected!("avx2") && std::is_x86_feature_detected!("sse4.2") {
+            test_snapshot_roundtrip::<avx2::Utf8ValidatorImp>();
+            test_snapshot_between::<avx2::Utf8ValidatorImp, sse42::Utf8ValidatorImp>();
+            test_snapshot_between::<sse42::Utf8ValidatorImp, avx2::Utf8ValidatorImp>();
+            test_snapshot_between::<avx2::Utf8ValidatorImp, fallback::Utf8ValidatorImp>();
+            test_snapshot_between::<fallback::Utf8ValidatorImp, avx2::Utf8ValidatorImp>();
+        }
+    }
+
+    #[test]
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    fn test_snapshot_neon() {
+        use simdutf8::basic::imp::aarch64::neon;
+        use simdutf8::basic::imp::fallback;
+
+        test_snapshot_roundtrip::<neon::Utf8ValidatorImp>();
+        test_snapshot_between::<neon::Utf8ValidatorImp, fallback::Utf8ValidatorImp>();
+        test_snapshot_between::<fallback::Utf8ValidatorImp, neon::Utf8ValidatorImp>();
+    }
+
+    #[test]
+    #[cfg(feature = "portable_public_imp")]
+    fn test_snapshot_portable() {
+        use simdutf8::basic::imp::portable::{simd128, simd256};
+
+        test_snapshot_roundtrip::<simd128::Utf8ValidatorImp>();
+        test_snapshot_between::<simd128::Utf8ValidatorImp, simd256::Utf8ValidatorImp>();
+        test_snapshot_between::<simd256::Utf8ValidatorImp, simd128::Utf8ValidatorImp>();
+    }
+
     #[allow(unused)] // not used if not SIMD implementation is available
     fn test_chunked_streaming<T: simdutf8::basic::imp::ChunkedUtf8Validator>(
         input: &[u8],
@@ -575,3 +740,1213 @@ fn error_derives_compat() {
     assert_eq!(err, err2);
     assert!(!(err != err2));
 }
+
+fn naive_location(input: &[u8], valid_up_to: usize) -> (usize, usize, usize) {
+    let valid = std::str::from_utf8(&input[..valid_up_to]).unwrap();
+    let line_start = valid.rfind('\n').map_or(0, |pos| pos + 1);
+    (
+        valid.matches('\n').count() + 1,
+        valid[line_start..].chars().count() + 1,
+        valid.chars().count(),
+    )
+}
+
+#[test]
+fn diagnostics_location() {
+    use simdutf8::diagnostics::Report;
+
+    let line = "ab ö\n€ 😊 cd\n".as_bytes();
+    for repeat in [0, 1, 5, 9, 20, 64].iter() {
+        for invalid in [&b"\xFF"[..], b"\xE2\x82", b"\xF0\x9F\x98"].iter() {
+            let mut input = line.repeat_x(*repeat);
+            input.extend_from_slice(b"xyz");
+            input.extend_from_slice(invalid);
+            let err = compat_from_utf8(&input).unwrap_err();
+            let report = Report::new(&input, err);
+            assert_eq!(
+                (report.line(), report.column(), report.char_index()),
+                naive_location(&input, err.valid_up_to())
+            );
+            assert_eq!(report.invalid_bytes(), *invalid);
+            input.extend_from_slice(b"\nmore");
+            let err = compat_from_utf8(&input).unwrap_err();
+            assert_eq!(Report::new(&input, err).error(), err);
+        }
+    }
+}
+
+#[test]
+fn diagnostics_display() {
+    use simdutf8::diagnostics::Report;
+
+    let input = b"\xC3";
+    let err = compat_from_utf8(input).unwrap_err();
+    assert_eq!(
+        Report::new(input, err).to_string(),
+        "error: incomplete utf-8 byte sequence from index 0\n \
+         --> line 1, column 1, char index 0\n  \
+         |\n  \
+         | 00000000: c3  .\n  \
+         |           ^^"
+    );
+
+    let mut input = "öäü\n\n0123456789\x7F\u{80}\u{FFFF}\u{10FFFF}"
+        .as_bytes()
+        .to_owned();
+    input.extend_from_slice(b"\xE2\x82");
+    let err = compat_from_utf8(&input).unwrap_err();
+    assert_eq!(
+        Report::new(&input, err).to_string(),
+        "error: incomplete utf-8 byte sequence from index 28\n \
+         --> line 3, column 15, char index 19\n  \
+         |\n  \
+         | 00000014: 80 ef bf bf f4 8f bf bf e2 82  ..........\n  \
+         |                                   ^^^^^"
+    );
+}
+
+#[test]
+fn ascii() {
+    use simdutf8::ascii::{classify, first_non_ascii, is_ascii, Classification};
+
+    for len in 0..200 {
+        let input = b"a".repeat_x(len);
+        assert!(is_ascii(&input));
+        assert_eq!(first_non_ascii(&input), None);
+        assert_eq!(classify(&input), Classification::Ascii);
+        for pos in 0..len {
+            let mut input = input.clone();
+            input[pos] = 0x80;
+            assert!(!is_ascii(&input));
+            assert_eq!(first_non_ascii(&input), Some(pos));
+            assert_eq!(classify(&input), Classification::Invalid);
+            input[pos] = 0x7F;
+            assert_eq!(classify(&input), Classification::Ascii);
+        }
+    }
+    for prefix in [0, 1, 63, 64, 65, 127, 128, 1000].iter() {
+        let mut input = b"a".repeat_x(*prefix);
+        input.extend_from_slice("ö€😊".as_bytes());
+        input.extend_from_slice(&b"a".repeat_x(70));
+        assert_eq!(first_non_ascii(&input), Some(*prefix));
+        assert_eq!(classify(&input), Classification::Utf8);
+        input.push(0xF0);
+        assert_eq!(classify(&input), Classification::Invalid);
+    }
+}
+
+#[test]
+fn boundary_floor_ceil() {
+    use simdutf8::boundary::{ceil_char_boundary, floor_char_boundary};
+
+    let s = "a€ö😊b";
+    for index in 0..=s.len() + 1 {
+        let floor = (0..=index.min(s.len()))
+            .rev()
+            .find(|i| s.is_char_boundary(*i))
+            .unwrap();
+        let ceil = (index.min(s.len())..=s.len())
+            .find(|i| s.is_char_boundary(*i))
+            .unwrap();
+        assert_eq!(floor_char_boundary(s.as_bytes(), index), floor);
+        assert_eq!(ceil_char_boundary(s.as_bytes(), index), ceil);
+    }
+    // no boundary within reach
+    assert_eq!(floor_char_boundary(b"a\x80\x80\x80\x80", 4), 4);
+    assert_eq!(ceil_char_boundary(b"\x80\x80\x80\x80a", 0), 0);
+}
+
+#[test]
+fn boundary_truncate_resync() {
+    use simdutf8::boundary::{resync, truncate};
+
+    let s = "ö€😊".repeat(30);
+    for max_len in 0..=s.len() + 1 {
+        let truncated = truncate(s.as_bytes(), max_len).unwrap();
+        let budget = max_len.min(s.len());
+        assert!(truncated.len() <= budget && budget - truncated.len() <= 3);
+        assert!(s.starts_with(truncated));
+    }
+    for start in 0..s.len() {
+        let rest = resync(&s.as_bytes()[start..]).unwrap();
+        assert!(s.ends_with(rest));
+        assert!(start + 3 >= s.len() - rest.len());
+    }
+
+    let mut input = b"\x80\x80".to_vec();
+    input.extend_from_slice(&b"a".repeat_x(100));
+    input.push(0xFF);
+    let err = resync(&input).unwrap_err();
+    assert_eq!(err.valid_up_to(), 102);
+    assert_eq!(err.error_len(), Some(1));
+    assert_eq!(truncate(&input, 102).unwrap_err().valid_up_to(), 0);
+    assert_eq!(truncate(&input[2..], 100).unwrap().len(), 100);
+    assert_eq!(truncate(&input[2..], 101).unwrap_err().valid_up_to(), 100);
+    assert_eq!(resync(b"\x80\x80\x80\x80a").unwrap_err().valid_up_to(), 0);
+}
+
+#[test]
+fn boundary_pieces() {
+    use simdutf8::boundary::pieces;
+
+    let s = "aö€😊".repeat(50);
+    for max_len in 4..20 {
+        let split: Vec<&str> = pieces(s.as_bytes(), max_len).unwrap().collect();
+        assert_eq!(split.concat(), s);
+        assert!(split.iter().all(|piece| piece.len() <= max_len));
+        assert!(split[..split.len() - 1]
+            .iter()
+            .all(|piece| piece.len() + 3 >= max_len));
+    }
+    assert_eq!(pieces(b"", 4).unwrap().next(), None);
+    assert_eq!(pieces(b"ab\xFF", 4).unwrap_err().valid_up_to(), 2);
+}
+
+#[test]
+fn incremental_revalidate() {
+    use simdutf8::incremental::revalidate;
+
+    let original = "aö€😊 ".repeat(40).into_bytes();
+    let replacements: [&[u8]; 8] = [
+        b"",
+        b"x",
+        "ü".as_bytes(),
+        "😊".as_bytes(),
+        b"\xC3",
+        b"\xA9",
+        b"\x80\x80",
+        b"\xF0\x9F\x98",
+    ];
+    let mut seed = 1_u32;
+    let mut rand = |max: usize| {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        (seed >> 8) as usize % max
+    };
+    for _ in 0..2000 {
+        let start = rand(original.len());
+        let end = start + rand(6).min(original.len() - start);
+        let replacement = replacements[rand(replacements.len())];
+        let mut buffer = original[..start].to_vec();
+        buffer.extend_from_slice(replacement);
+        buffer.extend_from_slice(&original[end..]);
+
+        let res = revalidate(&buffer, start..start + replacement.len());
+        match std::str::from_utf8(&buffer) {
+            Ok(s) => assert_eq!(res.unwrap(), s),
+            Err(expected) => {
+                let err = res.unwrap_err();
+                assert_eq!(err.valid_up_to(), expected.valid_up_to());
+                assert_eq!(err.error_len(), expected.error_len());
+            }
+        }
+    }
+}
+
+fn test_vectored(input: &[u8]) {
+    use simdutf8::basic::validate_vectored as basic_validate_vectored;
+    use simdutf8::compat::validate_vectored as compat_validate_vectored;
+
+    let expected = std::str::from_utf8(input);
+    let mut splits: Vec<usize> = (0..input.len()).step_by(7).collect();
+    splits.extend_from_slice(&[0, 1, 2, 3, 63, 64, 65, input.len() / 2, input.len()]);
+    for &split in splits.iter().filter(|split| **split <= input.len()) {
+        for &split2 in splits
+            .iter()
+            .filter(|split2| **split2 >= split && **split2 <= input.len())
+        {
+            let parts = [&input[..split], &input[split..split2], &input[split2..]];
+            assert_eq!(basic_validate_vectored(&parts).is_ok(), expected.is_ok());
+            let res = compat_validate_vectored(&parts);
+            match expected {
+                Ok(_) => assert!(res.is_ok()),
+                Err(expected) => {
+                    let err = res.unwrap_err();
+                    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+                    assert_eq!(err.error_len(), expected.error_len());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn vectored() {
+    let valid = "aö€😊".repeat(10);
+    test_vectored(valid.as_bytes());
+    for invalid in [
+        &b"\xFF"[..],
+        b"\xE2\x82",
+        b"\xF0\x9F\x98",
+        b"\xE2\x28\xA1",
+        b"\xED\xA0\x80",
+    ]
+    .iter()
+    {
+        for pos in [0, 1, 9, 30].iter() {
+            let mut input = valid.as_bytes()[..*pos].to_vec();
+            input.extend_from_slice(invalid);
+            test_vectored(&input);
+            input.extend_from_slice(valid.as_bytes());
+            test_vectored(&input);
+        }
+    }
+
+    let mut slices = vec![&b""[..]; 10];
+    slices.push("😊".as_bytes());
+    assert!(simdutf8::compat::validate_vectored(&slices).is_ok());
+    let io_slices = [
+        std::io::IoSlice::new(b"\xF0\x9F"),
+        std::io::IoSlice::new(b""),
+        std::io::IoSlice::new(b"\x98"),
+    ];
+    let err = simdutf8::compat::validate_vectored(&io_slices).unwrap_err();
+    assert_eq!((err.valid_up_to(), err.error_len()), (0, None));
+}
+
+#[test]
+fn batch() {
+    use simdutf8::batch::{validate_many_compat, validate_many_into};
+
+    let pieces: [&[u8]; 12] = [
+        b"",
+        b"a",
+        b"short",
+        "ö€😊".as_bytes(),
+        b"\xE2\x82",
+        b"\xAC",
+        b"\x80abc",
+        b"\xFF",
+        b"ab\xF0\x9F\x98",
+        "a long string that does not fit into a single block with the others".as_bytes(),
+        b"\xED\xA0\x80",
+        b"63 bytes..................................................\xC3",
+    ];
+    let mut inputs = Vec::new();
+    for i in 0..500 {
+        inputs.push(pieces[(i * 7 + i / 13) % pieces.len()]);
+    }
+    let mut results = vec![false; inputs.len()];
+    validate_many_into(&inputs, &mut results);
+    for (input, result) in inputs.iter().zip(results.iter()) {
+        assert_eq!(*result, std::str::from_utf8(input).is_ok(), "{:?}", input);
+    }
+
+    let err = validate_many_compat(&inputs).unwrap_err();
+    let index = results.iter().position(|valid| !valid).unwrap();
+    assert_eq!(err.index(), index);
+    assert_eq!(
+        err.error().valid_up_to(),
+        std::str::from_utf8(inputs[index])
+            .unwrap_err()
+            .valid_up_to()
+    );
+
+    let valid: Vec<&[u8]> = inputs
+        .iter()
+        .copied()
+        .filter(|input| std::str::from_utf8(input).is_ok())
+        .collect();
+    assert!(validate_many_compat(&valid).is_ok());
+    assert!(validate_many_compat(&[]).is_ok());
+}
+
+#[test]
+fn batch_offsets() {
+    use simdutf8::batch::{validate_offsets_i32, validate_offsets_i64};
+
+    fn first_invalid(offsets: &[i64], values: &[u8]) -> Option<usize> {
+        offsets
+            .windows(2)
+            .position(|w| std::str::from_utf8(&values[w[0] as usize..w[1] as usize]).is_err())
+    }
+
+    let mut values = "aö€😊".repeat(20).into_bytes();
+    values[60] = 0xFF;
+    let mut seed = 7_u32;
+    let mut rand = |max: usize| {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        (seed >> 8) as usize % max
+    };
+    for _ in 0..2000 {
+        let end = if rand(2) == 0 { 60 } else { values.len() };
+        let mut offsets = vec![rand(3) as i64];
+        while (*offsets.last().unwrap() as usize) < end {
+            let next = *offsets.last().unwrap() as usize + rand(12);
+            offsets.push(next.min(end) as i64);
+        }
+        let expected = first_invalid(&offsets, &values);
+        let res = validate_offsets_i64(&offsets, &values);
+        assert_eq!(res.err().map(|err| err.index()), expected, "{:?}", offsets);
+        let offsets32: Vec<i32> = offsets.iter().map(|o| *o as i32).collect();
+        let res = validate_offsets_i32(&offsets32, &values);
+        assert_eq!(res.err().map(|err| err.index()), expected);
+        if let Err(err) = res {
+            let string = &values[offsets[err.index()] as usize..offsets[err.index() + 1] as usize];
+            let expected = std::str::from_utf8(string).unwrap_err();
+            assert_eq!(err.error().valid_up_to(), expected.valid_up_to());
+            assert_eq!(err.error().error_len(), expected.error_len());
+        }
+    }
+    assert!(validate_offsets_i32(&[], b"").is_ok());
+    assert!(validate_offsets_i32(&[3], b"\xFF\xFF\xFF\xFF").is_ok());
+}
+
+#[test]
+#[should_panic(expected = "offsets must not decrease")]
+fn batch_offsets_decreasing() {
+    let _ = simdutf8::batch::validate_offsets_i32(&[0, 2, 1], b"abc");
+}
+
+#[test]
+fn batch_fixed_width() {
+    use simdutf8::batch::{validate_fixed_width, Padding};
+
+    let mut buffer = "aö€😊".repeat(20).into_bytes();
+    buffer[60] = 0xFF;
+    for stride in 1..=buffer.len() {
+        let len = buffer.len() / stride * stride;
+        for &end in &[len, 60 / stride * stride] {
+            let buffer = &buffer[..end];
+            let expected = buffer
+                .chunks(stride)
+                .position(|field| std::str::from_utf8(field).is_err());
+            let res = validate_fixed_width(buffer, stride, Padding::Nul);
+            assert_eq!(res.as_ref().err().map(|err| err.index()), expected);
+            match res {
+                Ok(fields) => {
+                    assert_eq!(fields.len(), end / stride);
+                    assert_eq!(fields.collect::<String>().as_bytes(), buffer);
+                }
+                Err(err) => {
+                    let field = &buffer[err.index() * stride..(err.index() + 1) * stride];
+                    let expected = std::str::from_utf8(field).unwrap_err();
+                    assert_eq!(err.error().valid_up_to(), expected.valid_up_to());
+                    assert_eq!(err.error().error_len(), expected.error_len());
+                }
+            }
+        }
+    }
+
+    let fields: Vec<&str> = validate_fixed_width(b"ab\0\0\0\0\0\0c\0 \0", 4, Padding::Nul)
+        .unwrap()
+        .collect();
+    assert_eq!(fields, ["ab", "", "c\0 "]);
+    let fields: Vec<&str> = validate_fixed_width("ä  ß \0ab  ".as_bytes(), 4, Padding::Space)
+        .unwrap()
+        .collect();
+    assert_eq!(fields, ["ä", "ß \0", "ab"]);
+    assert_eq!(
+        validate_fixed_width(b"", 3, Padding::Space)
+            .unwrap()
+            .count(),
+        0
+    );
+}
+
+#[test]
+#[should_panic(expected = "buffer length must be a multiple of the stride")]
+fn batch_fixed_width_remainder() {
+    let _ = simdutf8::batch::validate_fixed_width(b"abcde", 2, simdutf8::batch::Padding::Nul);
+}
+
+#[test]
+#[should_panic(expected = "destination must not be shorter than the source")]
+fn validate_and_copy_short_destination() {
+    let mut dst = [std::mem::MaybeUninit::<u8>::uninit(); 3];
+    let _ = simdutf8::compat::validate_and_copy(b"abcd", &mut dst);
+}
+
+#[test]
+fn cstr() {
+    use simdutf8::cstr::until_nul;
+
+    let mut input = "aö€😊".repeat(20).into_bytes();
+    let len = input.len();
+    for &nul in &[0, 1, 3, 63, 64, 65, 100, len] {
+        for &bad in &[0, 1, 2, 40, 63, 64, 99, 100, 101, 120, len - 1] {
+            let mut input = input.clone();
+            if nul < len {
+                input[nul] = 0;
+            }
+            input[bad] = 0xFF;
+            let end = input.iter().position(|&b| b == 0).unwrap_or(len);
+            let res = until_nul(&input);
+            match std::str::from_utf8(&input[..end]) {
+                Ok(expected) => assert_eq!(res.unwrap(), expected),
+                Err(expected) => {
+                    let err = res.unwrap_err();
+                    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+                    assert_eq!(err.error_len(), expected.error_len());
+                }
+            }
+        }
+    }
+    // NUL in the middle of a char
+    input[4] = 0;
+    let err = until_nul(&input).unwrap_err();
+    assert_eq!((err.valid_up_to(), err.error_len()), (3, None));
+    assert_eq!(until_nul(b"").unwrap(), "");
+}
+
+#[test]
+fn validate_until() {
+    use simdutf8::compat::validate_until;
+
+    let input = "ab\r\nö€😊".repeat(20).into_bytes();
+    let len = input.len();
+    for &delim in b"\r\nx" {
+        for &bad in &[0, 1, 2, 40, 63, 64, 99, 100, 101, 120, len - 1] {
+            let mut input = input.clone();
+            input[bad] = 0xFF;
+            let end = input.iter().position(|&b| b == delim);
+            let res = validate_until(&input, delim);
+            match std::str::from_utf8(&input[..end.unwrap_or(len)]) {
+                Ok(expected) => assert_eq!(res.unwrap(), (expected, end)),
+                Err(expected) => {
+                    let err = res.unwrap_err();
+                    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+                    assert_eq!(err.error_len(), expected.error_len());
+                }
+            }
+        }
+    }
+    assert_eq!(validate_until(b"", b'\n').unwrap(), ("", None));
+    assert_eq!(validate_until(b"\n", b'\n').unwrap(), ("", Some(0)));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn cstr_std() {
+    use simdutf8::cstr::{from_cstr, from_ptr};
+
+    let valid = std::ffi::CString::new("Grüße".repeat(20)).unwrap();
+    assert_eq!(from_cstr(&valid).unwrap(), "Grüße".repeat(20));
+    assert_eq!(
+        unsafe { from_ptr(valid.as_ptr()) }.unwrap(),
+        "Grüße".repeat(20)
+    );
+    let invalid = std::ffi::CString::new(&b"ab\xC3"[..]).unwrap();
+    let err = unsafe { from_ptr(invalid.as_ptr()) }.unwrap_err();
+    assert_eq!((err.valid_up_to(), err.error_len()), (2, None));
+}
+
+#[test]
+#[cfg(all(feature = "std", unix))]
+fn os() {
+    use simdutf8::os::{os_str_to_str, os_string_into_string, path_buf_into_string, path_to_str};
+    use std::ffi::{OsStr, OsString};
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+    use std::path::{Path, PathBuf};
+
+    let name = "/home/user/grüße/".repeat(10) + "file.txt";
+    assert_eq!(os_str_to_str(OsStr::new(&name)).unwrap(), name);
+    assert_eq!(path_to_str(Path::new(&name)).unwrap(), name);
+    assert_eq!(os_string_into_string(OsString::from(&name)).unwrap(), name);
+    assert_eq!(path_buf_into_string(PathBuf::from(&name)).unwrap(), name);
+
+    let mut bad = name.clone().into_bytes();
+    bad[100] = 0xFF;
+    let expected = std::str::from_utf8(&bad).unwrap_err();
+    let err = path_to_str(Path::new(OsStr::from_bytes(&bad))).unwrap_err();
+    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+    assert_eq!(err.error_len(), expected.error_len());
+    let err = os_string_into_string(OsString::from_vec(bad.clone())).unwrap_err();
+    assert_eq!(err.utf8_error().valid_up_to(), expected.valid_up_to());
+    assert_eq!(err.into_inner().into_vec(), bad);
+    let err = path_buf_into_string(PathBuf::from(OsString::from_vec(bad.clone()))).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "invalid utf-8 sequence of 1 bytes from index {}",
+            expected.valid_up_to()
+        )
+    );
+    assert_eq!(err.into_inner().into_os_string().into_vec(), bad);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn builder() {
+    use simdutf8::builder::Utf8StringBuilder;
+    use std::io::Write;
+
+    let input = "aö€😊".repeat(30);
+    let bytes = input.as_bytes();
+    for &step in &[1, 2, 3, 5, 63, 64, 65, 200] {
+        let mut builder = Utf8StringBuilder::with_capacity(bytes.len());
+        for chunk in bytes.chunks(step) {
+            builder.push_bytes(chunk).unwrap();
+        }
+        assert_eq!(builder.len(), bytes.len());
+        assert_eq!(builder.into_string().unwrap(), input);
+
+        let mut builder = Utf8StringBuilder::new();
+        for chunk in bytes.chunks(step) {
+            builder.write_all(chunk).unwrap();
+        }
+        assert_eq!(builder.into_string().unwrap(), input);
+    }
+
+    let mut builder = Utf8StringBuilder::new();
+    assert!(builder.is_empty());
+    builder.extend_from_slice(&bytes[..100]).unwrap();
+    let err = builder.push_bytes(b"\x98\x8A\xFF").unwrap_err();
+    let expected = std::str::from_utf8(&[&bytes[..100], b"\x98\x8A\xFF"].concat()).unwrap_err();
+    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+    assert_eq!(err.error_len(), expected.error_len());
+    // the invalid bytes were not appended
+    assert_eq!(builder.len(), 100);
+    builder.push_bytes(&bytes[100..]).unwrap();
+    assert_eq!(builder.clone().into_string().unwrap(), input);
+    let err = builder
+        .write(b"\xC3")
+        .and_then(|_| builder.write(b"\xC3"))
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = builder.into_string().unwrap_err();
+    assert_eq!((err.valid_up_to(), err.error_len()), (bytes.len(), None));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn io_lines() {
+    use simdutf8::io::{lines_simd, read_line_simd, LineError};
+    use std::io::{BufRead, BufReader, Read};
+    type Line = Result<String, (usize, usize, Option<usize>)>;
+
+    let mut input = Vec::new();
+    for i in 0..40 {
+        input.extend_from_slice("aö€😊".repeat(i).as_bytes());
+        match i % 5 {
+            0 => input.push(0xFF),
+            1 => input.extend_from_slice(b"\r\n"),
+            3 => input.extend_from_slice(b"\xE2\x82\n"),
+            4 => input.extend_from_slice(b"\xF0\x9F\x98\n"),
+            _ => input.push(b'\n'),
+        }
+    }
+    input.extend_from_slice("end😊".as_bytes());
+
+    for &capacity in &[1, 2, 3, 7, 64, 100, 8192] {
+        let line_count = input.split(|&b| b == b'\n').count();
+        let expected: Vec<Line> = input
+            .split(|&b| b == b'\n')
+            .enumerate()
+            .map(|(line, bytes)| {
+                // an incomplete char before the newline is an invalid sequence
+                let mut with_newline = bytes.to_vec();
+                if line + 1 < line_count {
+                    with_newline.push(b'\n');
+                }
+                let bytes = if bytes.last() == Some(&b'\r') {
+                    &bytes[..bytes.len() - 1]
+                } else {
+                    bytes
+                };
+                std::str::from_utf8(&with_newline)
+                    .map(|_| std::str::from_utf8(bytes).unwrap().to_owned())
+                    .map_err(|err| (line + 1, err.valid_up_to(), err.error_len()))
+            })
+            .collect();
+        let lines: Vec<Line> = lines_simd(BufReader::with_capacity(capacity, &input[..]))
+            .map(|res| {
+                res.map_err(|err| {
+                    let err = err.get_ref().unwrap().downcast_ref::<LineError>().unwrap();
+                    (
+                        err.line(),
+                        err.error().valid_up_to(),
+                        err.error().error_len(),
+                    )
+                })
+            })
+            .collect();
+        assert_eq!(lines, expected, "capacity {}", capacity);
+
+        let std_lines: Vec<bool> = BufReader::with_capacity(capacity, &input[..])
+            .lines()
+            .map(|res| res.is_ok())
+            .collect();
+        assert_eq!(
+            lines.iter().map(Result::is_ok).collect::<Vec<_>>(),
+            std_lines
+        );
+    }
+
+    let mut reader = BufReader::with_capacity(3, &b"ab\xC3\xA4\nc\xFF\nd"[..]);
+    let mut buf = String::from("x");
+    assert_eq!(read_line_simd(&mut reader, &mut buf).unwrap(), 5);
+    assert_eq!(buf, "xabä\n");
+    let err = read_line_simd(&mut reader, &mut buf).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<simdutf8::compat::Utf8Error>();
+    assert_eq!(err.unwrap().valid_up_to(), 1);
+    assert_eq!(buf, "xabä\n");
+    assert_eq!(read_line_simd(&mut reader, &mut buf).unwrap(), 1);
+    assert_eq!(read_line_simd(&mut reader, &mut buf).unwrap(), 0);
+    assert_eq!(buf, "xabä\nd");
+
+    // an incomplete char before the newline
+    for &capacity in &[1, 2, 3, 4, 8192] {
+        let input = &b"ab\xF0\x9F\x98\ncd\n"[..];
+        let expected = std::str::from_utf8(&input[..6]).unwrap_err();
+        let mut reader = BufReader::with_capacity(capacity, input);
+        let mut buf = String::new();
+        let err = read_line_simd(&mut reader, &mut buf).unwrap_err();
+        let err = err
+            .get_ref()
+            .unwrap()
+            .downcast_ref::<simdutf8::compat::Utf8Error>()
+            .unwrap();
+        assert_eq!(err.valid_up_to(), expected.valid_up_to());
+        assert_eq!(err.error_len(), expected.error_len());
+        assert_eq!(read_line_simd(&mut reader, &mut buf).unwrap(), 3);
+        assert_eq!(buf, "cd\n");
+    }
+
+    // as with `BufRead::read_line()`, the bytes read before an I/O error are kept if they are
+    // valid UTF-8
+    for &(input, kept) in &[
+        (&b"ab\xC3\xA4"[..], "xabä"),
+        (b"ab\xC3", "x"),
+        (b"a\xFFb", "x"),
+    ] {
+        let failing = input.chain(FailingReader);
+        let mut reader = BufReader::with_capacity(2, failing);
+        let mut buf = String::from("x");
+        let err = read_line_simd(&mut reader, &mut buf).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Other);
+        assert_eq!(buf, kept);
+
+        let failing = input.chain(FailingReader);
+        let mut reader = BufReader::with_capacity(2, failing);
+        let mut buf = String::from("x");
+        assert!(reader.read_line(&mut buf).is_err());
+        assert_eq!(buf, kept);
+    }
+}
+
+#[cfg(feature = "std")]
+struct FailingReader;
+
+#[cfg(feature = "std")]
+impl std::io::Read for FailingReader {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Err(std::io::ErrorKind::Other.into())
+    }
+}
+
+#[cfg(feature = "std")]
+struct ChoppyReader<'a> {
+    input: &'a [u8],
+    step: usize,
+    interrupted: bool,
+}
+
+#[cfg(feature = "std")]
+impl std::io::Read for ChoppyReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.interrupted = !self.interrupted;
+        if self.interrupted {
+            return Err(std::io::ErrorKind::Interrupted.into());
+        }
+        let len = self.step.min(buf.len()).min(self.input.len());
+        buf[..len].copy_from_slice(&self.input[..len]);
+        self.input = &self.input[len..];
+        self.step = self.step % 7 + 1;
+        Ok(len)
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn io_read_to_string() {
+    use simdutf8::io::{fs_read_to_string, read_to_string};
+
+    let valid = "aö€😊".repeat(100);
+    let reader = ChoppyReader {
+        input: valid.as_bytes(),
+        step: 1,
+        interrupted: false,
+    };
+    assert_eq!(read_to_string(reader).unwrap(), valid);
+    assert_eq!(read_to_string(valid.as_bytes()).unwrap(), valid);
+    assert_eq!(read_to_string(&b""[..]).unwrap(), "");
+
+    for &truncate in &[1, 2, 3, 500] {
+        let mut invalid = valid.as_bytes()[..valid.len() - truncate].to_vec();
+        if truncate == 500 {
+            invalid[400] = 0xFF;
+        }
+        let expected = std::str::from_utf8(&invalid).unwrap_err();
+        let reader = ChoppyReader {
+            input: &invalid,
+            step: 3,
+            interrupted: false,
+        };
+        let err = read_to_string(reader).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        let err = err
+            .get_ref()
+            .unwrap()
+            .downcast_ref::<simdutf8::compat::Utf8Error>();
+        assert_eq!(err.unwrap().valid_up_to(), expected.valid_up_to());
+        assert_eq!(err.unwrap().error_len(), expected.error_len());
+    }
+
+    let path = std::env::temp_dir().join(format!("simdutf8-test-{}.txt", std::process::id()));
+    // larger than the maximum size of a single read
+    let large = valid.repeat(100);
+    assert_eq!(read_to_string(large.as_bytes()).unwrap(), large);
+    std::fs::write(&path, &large).unwrap();
+    assert_eq!(fs_read_to_string(&path).unwrap(), large);
+    std::fs::write(&path, b"ab\xFF").unwrap();
+    let err = fs_read_to_string(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(fs_read_to_string(&path).is_err());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn io_utf8_reader() {
+    use simdutf8::io::Utf8Reader;
+
+    let valid = "aö€😊".repeat(100);
+    for &capacity in &[4, 5, 64, 8192] {
+        let mut reader = Utf8Reader::with_capacity(
+            capacity,
+            ChoppyReader {
+                input: valid.as_bytes(),
+                step: 1,
+                interrupted: false,
+            },
+        );
+        let mut res = String::new();
+        loop {
+            let chunk = reader.read_chunk().unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            assert!(chunk.len() <= capacity);
+            res.push_str(chunk);
+        }
+        assert_eq!(res, valid);
+        assert_eq!(reader.read_chunk().unwrap(), "");
+    }
+
+    for &truncate in &[1, 2, 3, 500] {
+        let mut invalid = valid.as_bytes()[..valid.len() - truncate].to_vec();
+        if truncate == 500 {
+            invalid[400] = 0xFF;
+        }
+        let expected = std::str::from_utf8(&invalid).unwrap_err();
+        let mut reader = Utf8Reader::with_capacity(7, &invalid[..]);
+        let mut len = 0;
+        let err = loop {
+            match reader.read_chunk() {
+                Ok(chunk) => len += chunk.len(),
+                Err(err) => break err,
+            }
+        };
+        assert!(len <= expected.valid_up_to());
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        let err = err
+            .get_ref()
+            .unwrap()
+            .downcast_ref::<simdutf8::compat::Utf8Error>();
+        assert_eq!(err.unwrap().valid_up_to(), expected.valid_up_to());
+        assert_eq!(err.unwrap().error_len(), expected.error_len());
+        // the error is sticky
+        assert!(reader.read_chunk().is_err());
+    }
+}
+
+#[test]
+#[should_panic(expected = "capacity must be at least four")]
+#[cfg(feature = "std")]
+fn io_utf8_reader_small_capacity() {
+    let _ = simdutf8::io::Utf8Reader::with_capacity(3, &b""[..]);
+}
+
+#[test]
+#[cfg(all(feature = "std", scoped_threads))]
+fn validate_parallel() {
+    use simdutf8::compat::validate_parallel;
+
+    let valid = "aö€😊".repeat(20_000).into_bytes();
+    let len = valid.len();
+    for threads in 0..6 {
+        assert!(validate_parallel(&valid, threads).is_ok());
+        let err = validate_parallel(&valid[..len - 1], threads).unwrap_err();
+        assert_eq!((err.valid_up_to(), err.error_len()), (len - 4, None));
+    }
+    // errors around the nominal split points, including chars truncated by them
+    let mut positions = vec![0, 1, len / 2, len - 1];
+    for segments in 2..4 {
+        for i in 1..segments {
+            let split = len / segments * i;
+            positions.extend(split - 4..split + 4);
+        }
+    }
+    for &pos in &positions {
+        for &bytes in &[&[0xFF_u8][..], b"\xE2\x82a", b"\xF0\x9F\x98a"] {
+            let mut input = valid.clone();
+            let end = (pos + bytes.len()).min(len);
+            input.splice(pos..end, bytes.iter().copied());
+            let expected = std::str::from_utf8(&input).unwrap_err();
+            for threads in 1..4 {
+                let err = validate_parallel(&input, threads).unwrap_err();
+                assert_eq!(
+                    err.valid_up_to(),
+                    expected.valid_up_to(),
+                    "{} {}",
+                    pos,
+                    threads
+                );
+                assert_eq!(err.error_len(), expected.error_len(), "{} {}", pos, threads);
+            }
+        }
+    }
+    // runs of continuation bytes at the split points
+    let mut input = valid.clone();
+    for b in &mut input[len / 2 - 8..len / 2 + 8] {
+        *b = 0x80;
+    }
+    let expected = std::str::from_utf8(&input).unwrap_err();
+    let err = validate_parallel(&input, 2).unwrap_err();
+    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+    assert_eq!(err.error_len(), expected.error_len());
+}
+
+#[test]
+fn merge_partial_states() {
+    use simdutf8::merge::PartialState;
+
+    const BYTES: [u8; 9] = [b'a', 0x80, 0x90, 0xC3, 0xED, 0xA0, 0xF0, 0xF4, 0xFF];
+    let mut input = [0_u8; 5];
+    for n in 0..BYTES.len().pow(5) {
+        let mut rest = n;
+        for b in &mut input {
+            *b = BYTES[rest % BYTES.len()];
+            rest /= BYTES.len();
+        }
+        let expected = std::str::from_utf8(&input)
+            .map(|_| ())
+            .map_err(|err| (err.valid_up_to(), err.error_len()));
+        let check = |state: PartialState| {
+            assert_eq!(state.len(), input.len());
+            let res = state
+                .finish()
+                .map_err(|err| (err.valid_up_to(), err.error_len()));
+            assert_eq!(res, expected, "{:x?}", input);
+        };
+        check(PartialState::validate(&input));
+        for i in 0..=input.len() {
+            for j in i..=input.len() {
+                let a = PartialState::validate(&input[..i]);
+                let b = PartialState::validate(&input[i..j]);
+                let c = PartialState::validate(&input[j..]);
+                let left = a.merge(b).merge(c);
+                let right = a.merge(b.merge(c));
+                assert_eq!(left, right, "{:x?} {} {}", input, i, j);
+                check(left);
+            }
+        }
+    }
+}
+
+#[test]
+fn merge_partial_states_long() {
+    use simdutf8::merge::PartialState;
+
+    let valid = "aö€😊".repeat(100).into_bytes();
+    let state = valid
+        .chunks(13)
+        .map(PartialState::validate)
+        .fold(PartialState::new(), PartialState::merge);
+    assert!(state.finish().is_ok());
+    assert!(PartialState::new().is_empty());
+
+    let mut invalid = valid;
+    invalid[777] = 0xFF;
+    let expected = std::str::from_utf8(&invalid).unwrap_err();
+    let err = invalid
+        .chunks(64)
+        .rev()
+        .map(PartialState::validate)
+        .fold(PartialState::new(), |acc, state| state.merge(acc))
+        .finish()
+        .unwrap_err();
+    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+    assert_eq!(err.error_len(), expected.error_len());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn line_index() {
+    use simdutf8::line_index::{LineIndex, LineIndexBuilder, Utf16Position};
+
+    let text = "aö\n€😊\r\n\n".repeat(50) + "last 😊 line";
+    let expected: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let index = LineIndex::new(text.as_bytes()).unwrap();
+    assert_eq!(index.line_starts(), &expected[..]);
+    assert_eq!(index.len(), text.len());
+    for chunk_len in &[1, 3, 64, 100] {
+        let mut builder = LineIndexBuilder::new();
+        for chunk in text.as_bytes().chunks(*chunk_len) {
+            builder.update(chunk).unwrap();
+        }
+        assert_eq!(builder.finish().unwrap(), index);
+    }
+
+    for (offset, _) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        let line = index.line_of(offset).unwrap();
+        let start = expected[line];
+        let position = index.to_utf16_position(&text, offset).unwrap();
+        assert_eq!(position.line, line);
+        assert_eq!(position.column, text[start..offset].encode_utf16().count());
+        if !text[offset..].starts_with('\n') {
+            assert_eq!(index.from_utf16_position(&text, position), Some(offset));
+        }
+    }
+    // inside a char, past the end and in the middle of a surrogate pair
+    assert_eq!(index.to_utf16_position(&text, 2), None);
+    assert_eq!(index.to_utf16_position(&text, text.len() + 1), None);
+    let pos = |line, column| Utf16Position { line, column };
+    assert_eq!(index.from_utf16_position(&text, pos(1, 2)), Some(7));
+    assert_eq!(index.from_utf16_position(&text, pos(1, 99)), Some(11));
+    assert_eq!(index.from_utf16_position(&text, pos(0, 99)), Some(3));
+    assert_eq!(
+        index.from_utf16_position(&text, pos(index.line_count(), 0)),
+        None
+    );
+    assert_eq!(index.line_range(1), Some(4..13));
+    assert_eq!(index.line_of(text.len() + 1), None);
+
+    let empty = LineIndex::new(b"").unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.line_starts(), &[0]);
+
+    let mut invalid = text.into_bytes();
+    invalid[300] = 0xFF;
+    let expected = std::str::from_utf8(&invalid).unwrap_err();
+    let err = LineIndex::new(&invalid).unwrap_err();
+    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+    let mut builder = LineIndexBuilder::new();
+    let err = invalid
+        .chunks(7)
+        .map(|chunk| builder.update(chunk))
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+    assert_eq!(err.error_len(), expected.error_len());
+    let mut builder = LineIndexBuilder::new();
+    builder.update(b"a\n\xF0\x9F").unwrap();
+    assert_eq!(builder.line_index().line_count(), 2);
+    assert_eq!(builder.finish().unwrap_err().valid_up_to(), 2);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn char_index() {
+    use simdutf8::char_index::CharIndex;
+
+    for text in &[
+        String::new(),
+        "abc".to_string(),
+        "a".repeat(64),
+        "aö€😊".repeat(64),
+        "€".repeat(100) + "x",
+    ] {
+        let index = CharIndex::new(text.as_bytes()).unwrap();
+        assert_eq!(index.len(), text.len());
+        assert_eq!(index.is_empty(), text.is_empty());
+        assert_eq!(index.char_count(), text.chars().count());
+        let offsets = text
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain(std::iter::once(text.len()));
+        for (i, offset) in offsets.enumerate() {
+            assert_eq!(index.byte_offset(text, i), Some(offset));
+            assert_eq!(index.char_index(text, offset), Some(i));
+        }
+        assert_eq!(index.byte_offset(text, index.char_count() + 1), None);
+        assert_eq!(index.char_index(text, text.len() + 1), None);
+    }
+
+    let mut invalid = "aö€😊".repeat(64).into_bytes();
+    invalid[333] = 0xC3;
+    let expected = std::str::from_utf8(&invalid).unwrap_err();
+    let err = CharIndex::new(&invalid).unwrap_err();
+    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+    assert_eq!(err.error_len(), expected.error_len());
+}
+
+#[test]
+fn utf16_offsets() {
+    use simdutf8::utf16::{
+        utf16_len, utf16_to_utf8_offset, utf16_to_utf8_offsets, utf8_to_utf16_offset,
+        utf8_to_utf16_offsets,
+    };
+
+    for text in &[
+        "",
+        "abc",
+        "😊",
+        "aö€😊",
+        &"aö€😊".repeat(50),
+        &("x".repeat(63) + &"😊".repeat(40)),
+    ] {
+        assert_eq!(utf16_len(text), text.encode_utf16().count());
+        let mut offsets = Vec::new();
+        let mut utf16_offsets = Vec::new();
+        let mut units = 0;
+        let ends = std::iter::once((text.len(), '\0'));
+        for (offset, c) in text.char_indices().chain(ends) {
+            offsets.push(offset);
+            utf16_offsets.push(units);
+            assert_eq!(utf8_to_utf16_offset(text, offset), Some(units));
+            assert_eq!(utf16_to_utf8_offset(text, units), Some(offset));
+            if c.len_utf16() == 2 {
+                assert_eq!(utf16_to_utf8_offset(text, units + 1), None);
+            }
+            units += c.len_utf16();
+        }
+        assert_eq!(utf8_to_utf16_offset(text, text.len() + 1), None);
+        assert_eq!(utf16_to_utf8_offset(text, utf16_len(text) + 1), None);
+
+        // sorted, reversed and with invalid offsets
+        let mut results = vec![None; offsets.len()];
+        utf8_to_utf16_offsets(text, &offsets, &mut results);
+        let expected: Vec<_> = utf16_offsets.iter().map(|&units| Some(units)).collect();
+        assert_eq!(results, expected);
+        utf16_to_utf8_offsets(text, &utf16_offsets, &mut results);
+        let expected: Vec<_> = offsets.iter().map(|&offset| Some(offset)).collect();
+        assert_eq!(results, expected);
+        let rev_offsets: Vec<_> = offsets.iter().rev().cloned().collect();
+        let rev_utf16_offsets: Vec<_> = utf16_offsets.iter().rev().cloned().collect();
+        utf8_to_utf16_offsets(text, &rev_offsets, &mut results);
+        let expected: Vec<_> = rev_utf16_offsets.iter().map(|&units| Some(units)).collect();
+        assert_eq!(results, expected);
+        utf16_to_utf8_offsets(text, &rev_utf16_offsets, &mut results);
+        let expected: Vec<_> = rev_offsets.iter().map(|&offset| Some(offset)).collect();
+        assert_eq!(results, expected);
+        let mut results = [Some(0); 2];
+        utf8_to_utf16_offsets(text, &[text.len() + 1, 0], &mut results);
+        assert_eq!(results, [None, Some(0)]);
+        utf16_to_utf8_offsets(text, &[utf16_len(text) + 1, 0], &mut results);
+        assert_eq!(results, [None, Some(0)]);
+    }
+}
+
+#[test]
+fn bitmap_block_masks() {
+    use simdutf8::bitmap::{block_masks, BlockMasks, BlockValidator};
+
+    let valid = "aö€😊 ascii text".repeat(20).into_bytes();
+    let mut inputs = vec![valid.clone()];
+    for &pos in &[0, 1, 62, 63, 64, 65, 127, 128, 200] {
+        for &b in &[0x80, 0xC0, 0xC3, 0xE0, 0xED, 0xF0, 0xF4, 0xF5, 0xFF, b'a'] {
+            let mut input = valid.clone();
+            input[pos] = b;
+            inputs.push(input);
+        }
+    }
+    for input in &inputs {
+        let blocks = input.chunks(64).count();
+        let mut masks = vec![BlockMasks::default(); blocks];
+        let res = block_masks(input, &mut masks);
+        assert_eq!(res.is_ok(), std::str::from_utf8(input).is_ok());
+        // half blocks are checked by the scalar implementation
+        let mut validator = BlockValidator::new();
+        let mut half_masks = [BlockMasks::default(); 1];
+        for (i, half) in input.chunks(32).enumerate() {
+            let _ = validator.update(half, &mut half_masks);
+            let shift = (i % 2) * 32;
+            let expected = masks[i / 2];
+            assert_eq!(
+                half_masks[0].char_starts,
+                (expected.char_starts >> shift) & 0xFFFF_FFFF
+            );
+            assert_eq!(
+                half_masks[0].errors,
+                (expected.errors >> shift) & 0xFFFF_FFFF
+            );
+        }
+        assert_eq!(validator.finish().is_ok(), res.is_ok());
+        for (i, block) in input.chunks(64).enumerate() {
+            for (j, &b) in block.iter().enumerate() {
+                let is_start = (masks[i].char_starts >> j) & 1 == 1;
+                assert_eq!(is_start, b & 0xC0 != 0x80);
+            }
+        }
+    }
+
+    let mut masks = [BlockMasks::default(); 1];
+    let mut validator = BlockValidator::new();
+    assert!(validator.update(b"ab\xF0\x9F", &mut masks).is_ok());
+    assert!(validator.finish().is_err());
+    assert!(validator.update(b"\x98\x8A", &mut masks).is_ok());
+    assert!(validator.finish().is_ok());
+    assert!(validator.update(b"\xE2", &mut masks).is_ok());
+    assert!(validator.update(b"x", &mut masks).is_err());
+    assert_eq!(masks[0].errors, 1);
+    assert!(validator.finish().is_err());
+}
+
+#[test]
+fn bitmap_byte_bitmaps() {
+    use simdutf8::bitmap::{validate_with_byte_bitmaps, ByteSet};
+
+    assert!(ByteSet::new(&[b'a'; 17]).is_none());
+    assert!(ByteSet::new(b"\xC3").is_none());
+    let set = ByteSet::new(b"{}[]:,\"\\\n").unwrap();
+    assert_eq!(set.as_bytes(), b"{}[]:,\"\\\n");
+
+    let text = "{\"k\\\"ö\": [1, \"😊\"]}\n".repeat(10);
+    for &len in &[0, 1, 20, 63, 64, 65, 66, 67, text.len()] {
+        let end = (0..=len)
+            .rev()
+            .find(|&end| text.is_char_boundary(end))
+            .unwrap();
+        let input = &text.as_bytes()[..end];
+        let blocks = input.chunks(64).count();
+        let mut bitmaps = vec![!0; blocks * set.len()];
+        assert_eq!(
+            validate_with_byte_bitmaps(input, &set, &mut bitmaps)
+                .unwrap()
+                .len(),
+            input.len()
+        );
+        for (i, block) in input.chunks(64).enumerate() {
+            for (j, &byte) in set.as_bytes().iter().enumerate() {
+                let expected = block
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &b)| b == byte)
+                    .fold(0, |mask, (k, _)| mask | 1 << k);
+                assert_eq!(bitmaps[i * set.len() + j], expected);
+            }
+        }
+    }
+
+    let empty = ByteSet::new(b"").unwrap();
+    assert!(empty.is_empty());
+    assert!(validate_with_byte_bitmaps(text.as_bytes(), &empty, &mut []).is_ok());
+
+    let mut invalid = text.into_bytes();
+    invalid[100] = 0xFF;
+    let expected = std::str::from_utf8(&invalid).unwrap_err();
+    let mut bitmaps = vec![0; 4 * set.len()];
+    let err = validate_with_byte_bitmaps(&invalid, &set, &mut bitmaps).unwrap_err();
+    assert_eq!(err.valid_up_to(), expected.valid_up_to());
+    assert_eq!(err.error_len(), expected.error_len());
+}